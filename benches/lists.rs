@@ -0,0 +1,336 @@
+// Dependency-free benchmark of every list against the std collections.
+//
+// Run with `cargo bench --bench lists`, results are printed as CSV on stdout:
+//
+//   benchmark,list,elements,runs,min_ns,median_ns,median_ns_per_elem
+//
+// `cargo bench --bench lists -- --quick` shrinks every workload 100x for a
+// smoke run, and any other free argument filters benchmarks by substring of
+// `benchmark/list`, e.g. `cargo bench --bench lists -- drop/sixth`.
+//
+// Not every list supports every workload (e.g. `fourth` can't iterate),
+// those rows are simply skipped.
+
+use std::collections::{LinkedList, VecDeque};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ELEMENTS: usize = 1_000_000;
+const DROP_ELEMENTS: usize = 10_000_000;
+const RUNS: usize = 5;
+
+// The operations every list has, in its natural order: a stack pushes and
+// pops at the same end, a queue at opposite ends.
+trait Bench: Sized {
+    const NAME: &'static str;
+    fn new() -> Self;
+    fn push(&mut self, elem: u64);
+    fn pop(&mut self) -> Option<u64>;
+
+    // optional workloads, `None` if the list doesn't support them
+    fn sum(&self) -> Option<u64> {
+        None
+    }
+    fn duplicate(&self) -> Option<Self> {
+        None
+    }
+}
+
+impl Bench for lists::first::List<u64> {
+    const NAME: &'static str = "first";
+    fn new() -> Self {
+        Self::new()
+    }
+    fn push(&mut self, elem: u64) {
+        self.push(elem)
+    }
+    fn pop(&mut self) -> Option<u64> {
+        self.pop()
+    }
+    fn sum(&self) -> Option<u64> {
+        Some(self.iter().sum())
+    }
+    fn duplicate(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+}
+
+impl Bench for lists::second::List<u64> {
+    const NAME: &'static str = "second";
+    fn new() -> Self {
+        Self::new()
+    }
+    fn push(&mut self, elem: u64) {
+        self.push(elem)
+    }
+    fn pop(&mut self) -> Option<u64> {
+        self.pop()
+    }
+    fn sum(&self) -> Option<u64> {
+        Some(self.iter().sum())
+    }
+    fn duplicate(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+}
+
+impl Bench for lists::fourth::List<u64> {
+    const NAME: &'static str = "fourth";
+    fn new() -> Self {
+        Self::new()
+    }
+    fn push(&mut self, elem: u64) {
+        self.push_back(elem)
+    }
+    fn pop(&mut self) -> Option<u64> {
+        self.pop_front()
+    }
+    fn duplicate(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+}
+
+impl Bench for lists::fifth::List<u64> {
+    const NAME: &'static str = "fifth";
+    fn new() -> Self {
+        Self::new()
+    }
+    fn push(&mut self, elem: u64) {
+        self.push(elem)
+    }
+    fn pop(&mut self) -> Option<u64> {
+        self.pop()
+    }
+    fn sum(&self) -> Option<u64> {
+        Some(self.iter().sum())
+    }
+    fn duplicate(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+}
+
+impl Bench for lists::sixth::LinkedList<u64> {
+    const NAME: &'static str = "sixth";
+    fn new() -> Self {
+        Self::new()
+    }
+    fn push(&mut self, elem: u64) {
+        self.push_back(elem)
+    }
+    fn pop(&mut self) -> Option<u64> {
+        self.pop_front()
+    }
+    fn sum(&self) -> Option<u64> {
+        Some(self.iter().sum())
+    }
+    fn duplicate(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+}
+
+impl Bench for lists::unrolled::UnrolledList<u64, 32> {
+    const NAME: &'static str = "unrolled<32>";
+    fn new() -> Self {
+        Self::new()
+    }
+    fn push(&mut self, elem: u64) {
+        self.push_back(elem)
+    }
+    fn pop(&mut self) -> Option<u64> {
+        self.pop_front()
+    }
+    fn sum(&self) -> Option<u64> {
+        Some(self.iter().sum())
+    }
+    fn duplicate(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+}
+
+impl Bench for LinkedList<u64> {
+    const NAME: &'static str = "std::LinkedList";
+    fn new() -> Self {
+        Self::new()
+    }
+    fn push(&mut self, elem: u64) {
+        self.push_back(elem)
+    }
+    fn pop(&mut self) -> Option<u64> {
+        self.pop_front()
+    }
+    fn sum(&self) -> Option<u64> {
+        Some(self.iter().sum())
+    }
+    fn duplicate(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+}
+
+impl Bench for VecDeque<u64> {
+    const NAME: &'static str = "std::VecDeque";
+    fn new() -> Self {
+        Self::new()
+    }
+    fn push(&mut self, elem: u64) {
+        self.push_back(elem)
+    }
+    fn pop(&mut self) -> Option<u64> {
+        self.pop_front()
+    }
+    fn sum(&self) -> Option<u64> {
+        Some(self.iter().sum())
+    }
+    fn duplicate(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+}
+
+struct Config {
+    elements: usize,
+    drop_elements: usize,
+    filters: Vec<String>,
+}
+
+impl Config {
+    fn from_args() -> Self {
+        let mut config = Config {
+            elements: ELEMENTS,
+            drop_elements: DROP_ELEMENTS,
+            filters: Vec::new(),
+        };
+        for arg in std::env::args().skip(1) {
+            match arg.as_str() {
+                "--quick" => {
+                    config.elements /= 100;
+                    config.drop_elements /= 100;
+                }
+                // cargo passes `--bench` to custom harnesses
+                _ if arg.starts_with("--") => {}
+                _ => config.filters.push(arg),
+            }
+        }
+        config
+    }
+
+    fn enabled(&self, benchmark: &str, list: &str) -> bool {
+        let id = format!("{}/{}", benchmark, list);
+        self.filters.is_empty() || self.filters.iter().any(|f| id.contains(f.as_str()))
+    }
+}
+
+fn filled<L: Bench>(n: usize) -> L {
+    let mut list = L::new();
+    for i in 0..n as u64 {
+        list.push(i);
+    }
+    list
+}
+
+// Times `RUNS` runs of `routine`, each on a fresh input from `setup`.
+// Neither the setup nor dropping the routine's output is timed.
+fn measure<S, I, R, O>(mut setup: S, mut routine: R) -> Vec<Duration>
+where
+    S: FnMut() -> I,
+    R: FnMut(I) -> O,
+{
+    let mut samples = Vec::with_capacity(RUNS);
+    for _ in 0..RUNS {
+        let input = setup();
+        let start = Instant::now();
+        let output = routine(input);
+        samples.push(start.elapsed());
+        drop(output);
+    }
+    samples.sort();
+    samples
+}
+
+fn report(benchmark: &str, list: &str, elements: usize, samples: Vec<Duration>) {
+    let min = samples[0].as_nanos();
+    let median = samples[samples.len() / 2].as_nanos();
+    println!(
+        "{},{},{},{},{},{},{:.3}",
+        benchmark,
+        list,
+        elements,
+        samples.len(),
+        min,
+        median,
+        median as f64 / elements as f64
+    );
+}
+
+fn run<L: Bench>(config: &Config) {
+    let n = config.elements;
+
+    if config.enabled("push", L::NAME) {
+        let samples = measure(L::new, |mut list: L| {
+            for i in 0..n as u64 {
+                list.push(black_box(i));
+            }
+            list
+        });
+        report("push", L::NAME, n, samples);
+    }
+
+    if config.enabled("pop", L::NAME) {
+        let samples = measure(
+            || filled::<L>(n),
+            |mut list: L| {
+                while let Some(x) = list.pop() {
+                    black_box(x);
+                }
+                list
+            },
+        );
+        report("pop", L::NAME, n, samples);
+    }
+
+    // probe the optional workloads on an empty list
+    let supports_iter = L::new().sum().is_some();
+    let supports_clone = L::new().duplicate().is_some();
+
+    if supports_iter && config.enabled("iter", L::NAME) {
+        let list = filled::<L>(n);
+        let samples = measure(|| &list, |list| black_box(list.sum()));
+        report("iter", L::NAME, n, samples);
+    }
+
+    if supports_clone && config.enabled("clone", L::NAME) {
+        let list = filled::<L>(n);
+        let samples = measure(|| &list, |list| black_box(list.duplicate()));
+        report("clone", L::NAME, n, samples);
+    }
+
+    if config.enabled("drop", L::NAME) {
+        let samples = measure(|| filled::<L>(config.drop_elements), drop);
+        report("drop", L::NAME, config.drop_elements, samples);
+    }
+
+    if config.enabled("mixed", L::NAME) {
+        // grows by one element every round: push, push, pop
+        let samples = measure(L::new, |mut list: L| {
+            for i in 0..(n / 2) as u64 {
+                list.push(black_box(i));
+                list.push(black_box(i));
+                black_box(list.pop());
+            }
+            list
+        });
+        report("mixed", L::NAME, n, samples);
+    }
+}
+
+fn main() {
+    let config = Config::from_args();
+
+    println!("benchmark,list,elements,runs,min_ns,median_ns,median_ns_per_elem");
+    run::<lists::first::List<u64>>(&config);
+    run::<lists::second::List<u64>>(&config);
+    run::<lists::fourth::List<u64>>(&config);
+    run::<lists::fifth::List<u64>>(&config);
+    run::<lists::sixth::LinkedList<u64>>(&config);
+    run::<lists::unrolled::UnrolledList<u64, 32>>(&config);
+    run::<LinkedList<u64>>(&config);
+    run::<VecDeque<u64>>(&config);
+}
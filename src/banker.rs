@@ -0,0 +1,453 @@
+// "A Persistent Deque", a banker's deque built from two lists of "third.rs".
+// To learn about:
+//   1) Building a persistent structure out of another one
+//   2) Amortized complexity, and how persistence breaks it
+
+// Takeaways:
+//   1. The front half is a stack whose top is the first element, the back half
+//      a stack whose top is the last one. Both ends are the top of some stack,
+//      so pushing and popping at either end is a `prepend` or a `tail`.
+//   2. If one half runs empty the other one must be split, the bottom of one
+//      stack moves to the bottom of the other, reversed. That costs O(n).
+//   3. We rebalance as soon as one half is more than 3 times bigger than the
+//      other (the "banker's" invariant). After a rebalance both halves hold
+//      about n / 2 elements, so the next one is at least n / 3 operations away
+//      and they pay for it: O(1) amortized per operation.
+//   4. That argument assumes every version is used once. A persistent structure
+//      lets you keep an old version around and run the same O(n) rebalance
+//      again and again from it. So this deque is NOT O(1) amortized in general:
+//      it is as long as each version is only built upon once, otherwise a single
+//      operation costs O(n). Okasaki's banker's deque gets the bound for every
+//      use by making the halves lazy lists whose reversal is suspended and
+//      memoized, so all versions share it. "third.rs" lists are strict, that
+//      would need a lazy list of its own.
+//   5. Nodes are shared between versions, so elements can never be moved out of
+//      a node. The halves hold Rc<T> instead of T: a rebalance copies pointers
+//      into new nodes, never the elements themselves, and T needn't be Clone.
+//   6. Iterating walks the front half, then the back half bottom to top, which a
+//      stack can't do. The first `iter` to get there reverses the back half into
+//      another list and keeps it, for every version with the very same back
+//      half: all those made by front operations that didn't rebalance.
+//   7. The halves are "third.rs" lists, dropping a version drops its nodes
+//      iteratively and stops at the first node that another version still uses.
+
+use crate::third::{self, List};
+use std::cell::OnceCell;
+use std::rc::Rc;
+
+// one half is allowed to be up to this many times bigger than the other
+const BALANCE: usize = 3;
+
+pub struct Deque<T> {
+    front: List<Rc<T>>,
+    front_len: usize,
+    back: List<Rc<T>>,
+    back_len: usize,
+    // `back` bottom to top, built by the first `iter` that needs it
+    back_rev: Rc<OnceCell<List<Rc<T>>>>,
+}
+
+impl<T> Deque<T> {
+    pub fn new() -> Self {
+        Deque {
+            front: List::new(),
+            front_len: 0,
+            back: List::new(),
+            back_len: 0,
+            back_rev: Rc::default(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.front_len + self.back_len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // when one half is empty the other one holds at most one element
+    pub fn front(&self) -> Option<&T> {
+        self.front
+            .head()
+            .or_else(|| self.back.head())
+            .map(|elem| &**elem)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.back
+            .head()
+            .or_else(|| self.front.head())
+            .map(|elem| &**elem)
+    }
+
+    pub fn push_front(&self, elem: T) -> Deque<T> {
+        self.balanced(
+            self.front.prepend(Rc::new(elem)),
+            self.front_len + 1,
+            self.back.clone(),
+            self.back_len,
+        )
+    }
+
+    pub fn push_back(&self, elem: T) -> Deque<T> {
+        self.balanced(
+            self.front.clone(),
+            self.front_len,
+            self.back.prepend(Rc::new(elem)),
+            self.back_len + 1,
+        )
+    }
+
+    // the deque without its first element, like third::List::tail
+    pub fn pop_front(&self) -> Deque<T> {
+        if self.front_len == 0 {
+            return Self::new();
+        }
+        self.balanced(
+            self.front.tail(),
+            self.front_len - 1,
+            self.back.clone(),
+            self.back_len,
+        )
+    }
+
+    pub fn pop_back(&self) -> Deque<T> {
+        if self.back_len == 0 {
+            return Self::new();
+        }
+        self.balanced(
+            self.front.clone(),
+            self.front_len,
+            self.back.tail(),
+            self.back_len - 1,
+        )
+    }
+
+    // The new version, rebalanced if needed. It shares `back_rev` with `self`
+    // if it kept the same back half.
+    fn balanced(
+        &self,
+        front: List<Rc<T>>,
+        front_len: usize,
+        back: List<Rc<T>>,
+        back_len: usize,
+    ) -> Self {
+        let len = front_len + back_len;
+        if front_len > BALANCE * back_len + 1 {
+            let (front, back) = Self::shift(&front, len / 2, &back);
+            return Deque {
+                front,
+                front_len: len / 2,
+                back,
+                back_len: len - len / 2,
+                back_rev: Rc::default(),
+            };
+        }
+        if back_len > BALANCE * front_len + 1 {
+            let (back, front) = Self::shift(&back, len / 2, &front);
+            return Deque {
+                front,
+                front_len: len - len / 2,
+                back,
+                back_len: len / 2,
+                back_rev: Rc::default(),
+            };
+        }
+        let back_rev = if back.ptr_eq(&self.back) {
+            Rc::clone(&self.back_rev)
+        } else {
+            Rc::default()
+        };
+        return Deque {
+            front,
+            front_len,
+            back,
+            back_len,
+            back_rev,
+        };
+    }
+
+    // Keeps the top `keep` elements of `from`, its bottom goes reversed under
+    // the bottom of `to`. Neither list shares a suffix with the result, so
+    // every node is new, but it only copies a pointer.
+    fn shift(from: &List<Rc<T>>, keep: usize, to: &List<Rc<T>>) -> (List<Rc<T>>, List<Rc<T>>) {
+        let kept = from.take(keep);
+        let to = to.concat(&from.drop(keep).reverse());
+        (kept, to)
+    }
+}
+
+// front to back
+pub struct Iter<'a, T> {
+    front: third::Iter<'a, Rc<T>>,
+    // set once `front` runs out
+    back: Option<third::Iter<'a, Rc<T>>>,
+    deque: &'a Deque<T>,
+    len: usize,
+}
+
+impl<T> Deque<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            front: self.front.iter(),
+            back: None,
+            deque: self,
+            len: self.len(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let elem = self.front.next().or_else(|| {
+            let deque = self.deque;
+            self.back
+                .get_or_insert_with(|| deque.back_rev.get_or_init(|| deque.back.reverse()).iter())
+                .next()
+        })?;
+        self.len -= 1;
+        Some(&**elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+//////////////////////
+// other interfaces //
+//////////////////////
+
+impl<T> Default for Deque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// cheap, shares both halves
+impl<T> Clone for Deque<T> {
+    fn clone(&self) -> Self {
+        Deque {
+            front: self.front.clone(),
+            front_len: self.front_len,
+            back: self.back.clone(),
+            back_len: self.back_len,
+            back_rev: Rc::clone(&self.back_rev),
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Deque<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> FromIterator<T> for Deque<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Self::new(), |deque, elem| deque.push_back(elem))
+    }
+}
+
+impl<T: PartialEq> PartialEq for Deque<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+impl<T: Eq> Eq for Deque<T> {}
+
+#[cfg(test)]
+mod test {
+    use super::{Deque, BALANCE};
+    use crate::test_helpers::{panics, total_allocations, DropCounter};
+    use std::collections::VecDeque;
+
+    fn values(deque: &Deque<i32>) -> Vec<i32> {
+        deque.iter().copied().collect()
+    }
+
+    fn balanced<T>(deque: &Deque<T>) -> bool {
+        deque.front_len <= BALANCE * deque.back_len + 1
+            && deque.back_len <= BALANCE * deque.front_len + 1
+    }
+
+    #[test]
+    fn basics() {
+        let empty = Deque::new();
+        assert_eq!(empty.front(), None);
+        assert_eq!(empty.back(), None);
+        assert!(empty.pop_front().is_empty());
+        assert!(empty.pop_back().is_empty());
+
+        let one = empty.push_back(1);
+        assert_eq!(one.front(), Some(&1));
+        assert_eq!(one.back(), Some(&1));
+        assert!(one.pop_front().is_empty());
+        assert!(one.pop_back().is_empty());
+
+        let deque = one.push_back(2).push_back(3).push_front(0);
+        assert_eq!(values(&deque), vec![0, 1, 2, 3]);
+        assert_eq!(deque.len(), 4);
+        assert_eq!(deque.front(), Some(&0));
+        assert_eq!(deque.back(), Some(&3));
+
+        let popped = deque.pop_front().pop_back();
+        assert_eq!(values(&popped), vec![1, 2]);
+
+        // older versions are untouched
+        assert_eq!(values(&deque), vec![0, 1, 2, 3]);
+        assert_eq!(values(&one), vec![1]);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn one_end_only() {
+        // pushing on one end and popping on the other forces every element
+        // across the middle at some point
+        let mut deque = Deque::new();
+        for i in 0..100 {
+            deque = deque.push_front(i);
+            assert!(balanced(&deque));
+        }
+        for i in 0..100 {
+            assert_eq!(deque.back(), Some(&i));
+            deque = deque.pop_back();
+            assert!(balanced(&deque));
+        }
+        assert!(deque.is_empty());
+
+        for i in 0..100 {
+            deque = deque.push_back(i);
+        }
+        for i in 0..100 {
+            assert_eq!(deque.front(), Some(&i));
+            deque = deque.pop_front();
+        }
+        assert!(deque.is_empty());
+    }
+
+    #[test]
+    fn against_vecdeque() {
+        let mut deque = Deque::new();
+        let mut model = VecDeque::new();
+        let mut versions = Vec::new();
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        for i in 0..2000 {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            match seed % 5 {
+                0 => {
+                    deque = deque.push_front(i);
+                    model.push_front(i);
+                }
+                1 | 2 => {
+                    deque = deque.push_back(i);
+                    model.push_back(i);
+                }
+                3 => {
+                    deque = deque.pop_front();
+                    model.pop_front();
+                }
+                _ => {
+                    deque = deque.pop_back();
+                    model.pop_back();
+                }
+            }
+            assert!(balanced(&deque));
+            assert_eq!(deque.len(), model.len());
+            assert_eq!(deque.front(), model.front());
+            assert_eq!(deque.back(), model.back());
+            if i % 10 == 0 {
+                assert!(deque.iter().eq(model.iter()));
+            }
+            if i % 100 == 0 {
+                versions.push((deque.clone(), model.clone()));
+            }
+        }
+        // every version we kept still reads the same
+        for (deque, model) in &versions {
+            assert_eq!(values(deque), model.iter().copied().collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn traits() {
+        let deque: Deque<i32> = (1..=4).collect();
+        assert_eq!(format!("{:?}", deque), "[1, 2, 3, 4]");
+        assert_eq!(deque.iter().len(), 4);
+        assert_eq!(deque, deque.clone());
+        assert_ne!(deque, deque.pop_back());
+        assert_eq!(
+            deque,
+            Deque::default()
+                .push_front(2)
+                .push_front(1)
+                .push_back(3)
+                .push_back(4)
+        );
+    }
+
+    #[test]
+    fn iter_reverses_once() {
+        let deque: Deque<i32> = (0..100).collect();
+        assert!(deque.iter().eq((0..100).collect::<Vec<_>>().iter()));
+        // the same back half, already reversed
+        let before = total_allocations();
+        let popped = deque.pop_front().push_front(-1);
+        // a node and the Rc of the new element
+        assert_eq!(total_allocations() - before, 2);
+        assert!(popped.iter().skip(1).eq(deque.iter().skip(1)));
+        assert_eq!(total_allocations() - before, 2);
+
+        // a new back half, reversed again
+        let pushed = deque.push_back(100);
+        assert!(pushed.iter().eq((0..101).collect::<Vec<_>>().iter()));
+        let short = pushed.pop_back();
+        assert!(short.iter().eq(deque.iter()));
+        let mut iter = short.iter();
+        assert_eq!(iter.len(), 100);
+        iter.nth(97);
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.collect::<Vec<_>>(), vec![&98, &99]);
+    }
+
+    #[test]
+    fn drop_shared() {
+        let counter = DropCounter::new();
+        // a rebalance only copies pointers, the elements are never cloned
+        let deque: Deque<_> = (0..4).map(|id| counter.panic_on_clone(id)).collect();
+        let popped = deque.pop_front();
+        // only the popped element belonged to `deque` alone
+        drop(deque);
+        assert_eq!(counter.dropped(), vec![0]);
+        drop(popped);
+        assert_eq!(counter.count(), 4);
+    }
+
+    #[test]
+    fn drop_long_deque() {
+        let deque: Deque<i32> = (0..200_000).collect();
+        let copy = deque.pop_front().pop_back();
+        drop(deque);
+        drop(copy);
+    }
+
+    #[test]
+    fn drop_panic_long() {
+        // the halves drop like "third.rs" lists, a panicking element at the
+        // front can't make the million behind it drop recursively
+        let counter = DropCounter::new();
+        let deque = (1..=1_000_000).fold(
+            Deque::new().push_back(counter.panic_on_drop(0)),
+            |deque, id| deque.push_back(counter.track(id)),
+        );
+        assert!(panics(move || drop(deque)));
+        assert_eq!(counter.count(), 1_000_001);
+    }
+}
@@ -0,0 +1,474 @@
+// Conformance of sixth::LinkedList to std::collections::LinkedList.
+// Every case runs twice, once with `List` standing for std's list and once
+// for ours, and both runs must see the same thing. That's also how a user
+// would switch: nothing but the type alias changes. The cases follow std's
+// own tests. Cursors, `remove` and `retain` are unstable in std, so those
+// cases only run against ours, checked against std's documentation.
+
+use crate::sixth::LinkedList;
+use crate::test_helpers::{panics, DropCounter};
+
+macro_rules! conformance {
+    ($($name:ident $body:block)*) => {$(
+        #[test]
+        #[allow(clippy::useless_vec)]
+        fn $name() {
+            let theirs = {
+                #[allow(dead_code)]
+                type List<T> = std::collections::LinkedList<T>;
+                $body
+            };
+            let ours = {
+                #[allow(dead_code)]
+                type List<T> = crate::sixth::LinkedList<T>;
+                $body
+            };
+            assert_eq!(theirs, ours);
+        }
+    )*};
+}
+
+conformance! {
+    basic {
+        let mut seen = Vec::new();
+        let mut m = List::new();
+        seen.push((m.pop_front(), m.pop_back(), m.len(), m.is_empty()));
+        m.push_front(1);
+        seen.push((m.pop_front(), None, m.len(), m.is_empty()));
+        m.push_back(2);
+        m.push_back(3);
+        seen.push((m.pop_front(), m.pop_front(), m.len(), m.is_empty()));
+        for i in 0..10 {
+            m.push_back(i);
+            m.push_front(-i);
+        }
+        *m.front_mut().unwrap() *= 100;
+        *m.back_mut().unwrap() *= 100;
+        seen.push((m.front().copied(), m.back().copied(), m.len(), m.is_empty()));
+        seen.push((m.pop_back(), m.pop_front(), m.len(), m.is_empty()));
+        (seen, m.into_iter().collect::<Vec<_>>())
+    }
+
+    append {
+        let mut seen = Vec::new();
+        for (a, b) in [(0, 0), (0, 3), (3, 0), (4, 5)] {
+            let mut m: List<i32> = (0..a).collect();
+            let mut n: List<i32> = (10..10 + b).collect();
+            m.append(&mut n);
+            seen.push((m.len(), n.len(), n.is_empty()));
+            seen.push((m.front().copied().unwrap_or(-1) as usize, 0, false));
+            seen.push((m.back().copied().unwrap_or(-1) as usize, 0, false));
+            // both lists still work after the move
+            n.push_back(99);
+            m.push_back(100);
+            m.append(&mut n);
+            seen.push((m.iter().rev().map(|&x| x as usize).sum(), m.len(), false));
+        }
+        seen
+    }
+
+    split_off {
+        let mut seen = Vec::new();
+        for len in 0..6 {
+            for at in 0..=len {
+                let mut m: List<i32> = (0..len as i32).collect();
+                let mut n = m.split_off(at);
+                seen.push((m.iter().copied().collect::<Vec<_>>(), n.iter().rev().copied().collect::<Vec<_>>()));
+                m.push_back(-1);
+                n.push_front(-2);
+                seen.push((m.into_iter().collect(), n.into_iter().rev().collect()));
+            }
+        }
+        let too_far = panics(|| {
+            let mut m: List<i32> = (0..3).collect();
+            m.split_off(4);
+        });
+        (seen, too_far)
+    }
+
+    iterators {
+        let mut m: List<i32> = (0..7).collect();
+        let mut seen = Vec::new();
+        seen.push(m.iter().copied().collect::<Vec<_>>());
+        seen.push(m.iter().rev().copied().collect());
+        let mut it = m.iter();
+        let mut both_ends = Vec::new();
+        while let (Some(&a), b) = (it.next(), it.next_back()) {
+            both_ends.push(a);
+            both_ends.push(b.copied().unwrap_or(-1));
+            both_ends.push(it.len() as i32);
+        }
+        both_ends.push(it.next().is_none() as i32);
+        seen.push(both_ends);
+        let mut it = m.iter();
+        it.next();
+        let copy = it.clone();
+        seen.push(it.zip(copy).map(|(a, b)| a * b).collect());
+        for x in m.iter_mut().rev().take(3) {
+            *x *= 10;
+        }
+        for x in &mut m {
+            *x += 1;
+        }
+        let mut by_ref = Vec::new();
+        for x in &m {
+            by_ref.push(*x);
+        }
+        seen.push(by_ref);
+        seen.push(vec![m.iter_mut().size_hint().0 as i32, m.iter().rev().len() as i32]);
+        let mut owned = m.into_iter();
+        seen.push(vec![owned.next_back().unwrap(), owned.len() as i32]);
+        seen.push(owned.collect());
+        seen
+    }
+
+    traits {
+        use std::hash::{Hash, Hasher};
+        let hash = |list: &List<i32>| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            list.hash(&mut hasher);
+            hasher.finish()
+        };
+        let a: List<i32> = (0..5).collect();
+        let b = List::from([0, 1, 2, 3, 4]);
+        let c = List::from([0, 1, 2, 3]);
+        let empty: List<i32> = List::default();
+        (
+            (a == b, a == c, a < c, c < a, a.cmp(&b), empty < c),
+            (hash(&a) == hash(&b), hash(&a), hash(&empty)),
+            format!("{:?} {:?} {:?}", a, empty, a.clone()),
+            (a.contains(&4), a.contains(&5), empty.contains(&0)),
+        )
+    }
+
+    extend {
+        let mut m = List::new();
+        m.extend(vec![1, 2]);
+        m.extend(&[3, 4]);
+        m.extend(m.clone().iter());
+        let mut n: List<i32> = List::new();
+        n.extend(&m);
+        n.extend(Vec::<i32>::new());
+        (m.into_iter().collect::<Vec<_>>(), n.len())
+    }
+
+    clear {
+        let counter = DropCounter::new();
+        let mut m: List<_> = (0..4).map(|id| counter.track(id)).collect();
+        m.clear();
+        let after_clear = (m.len(), m.front().is_none(), counter.dropped());
+        m.push_back(counter.track(9));
+        m.push_front(counter.track(8));
+        let ids = m.iter().map(|t| t.id).collect::<Vec<_>>();
+        drop(m);
+        (after_clear, ids, counter.dropped())
+    }
+
+    drop_panic {
+        let counter = DropCounter::new();
+        let mut m = List::new();
+        m.push_back(counter.track(0));
+        m.push_back(counter.panic_on_drop(1));
+        m.push_back(counter.track(2));
+        let panicked = panics(move || drop(m));
+        (panicked, counter.dropped())
+    }
+
+    send_to_thread {
+        let m: List<i32> = (0..100).collect();
+        std::thread::spawn(move || m.iter().sum::<i32>()).join().unwrap()
+    }
+}
+
+// walks the list both ways and checks they agree, and with len
+fn check_links<T: PartialEq + std::fmt::Debug>(list: &LinkedList<T>) {
+    let forward: Vec<&T> = list.iter().collect();
+    let mut backward: Vec<&T> = list.iter().rev().collect();
+    backward.reverse();
+    assert_eq!(forward, backward);
+    assert_eq!(forward.len(), list.len());
+}
+
+fn values(list: &LinkedList<u32>) -> Vec<u32> {
+    list.iter().copied().collect()
+}
+
+#[test]
+fn remove_and_retain() {
+    let mut m: LinkedList<i32> = (0..8).collect();
+    assert_eq!(m.remove(0), 0);
+    assert_eq!(m.remove(6), 7);
+    assert_eq!(m.remove(2), 3);
+    check_links(&m);
+    assert_eq!(m.iter().copied().collect::<Vec<_>>(), vec![1, 2, 4, 5, 6]);
+    assert!(panics(|| {
+        LinkedList::from([1]).remove(1);
+    }));
+
+    m.retain(|&x| x % 2 == 0);
+    check_links(&m);
+    assert_eq!(m.iter().copied().collect::<Vec<_>>(), vec![2, 4, 6]);
+    m.retain_mut(|x| {
+        *x += 1;
+        *x != 5
+    });
+    assert_eq!(m.iter().copied().collect::<Vec<_>>(), vec![3, 7]);
+    m.retain(|_| false);
+    assert!(m.is_empty());
+    check_links(&m);
+}
+
+#[test]
+fn cursor_move_peek() {
+    let m = LinkedList::from([1u32, 2, 3, 4, 5, 6]);
+    let mut cursor = m.cursor_front();
+    assert_eq!(cursor.current(), Some(&1));
+    assert_eq!(cursor.peek_next(), Some(&2));
+    assert_eq!(cursor.peek_prev(), None);
+    assert_eq!(cursor.index(), Some(0));
+    cursor.move_prev();
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.peek_next(), Some(&1));
+    assert_eq!(cursor.peek_prev(), Some(&6));
+    assert_eq!(cursor.index(), None);
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.current(), Some(&2));
+    assert_eq!(cursor.peek_next(), Some(&3));
+    assert_eq!(cursor.peek_prev(), Some(&1));
+    assert_eq!(cursor.index(), Some(1));
+
+    let mut cursor = m.cursor_back();
+    assert_eq!(cursor.current(), Some(&6));
+    assert_eq!(cursor.peek_next(), None);
+    assert_eq!(cursor.peek_prev(), Some(&5));
+    assert_eq!(cursor.index(), Some(5));
+    cursor.move_next();
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.peek_next(), Some(&1));
+    assert_eq!(cursor.peek_prev(), Some(&6));
+    assert_eq!(cursor.index(), None);
+    cursor.move_prev();
+    cursor.move_prev();
+    assert_eq!(cursor.current(), Some(&5));
+    assert_eq!(cursor.peek_next(), Some(&6));
+    assert_eq!(cursor.peek_prev(), Some(&4));
+    assert_eq!(cursor.index(), Some(4));
+    assert_eq!((cursor.front(), cursor.back()), (Some(&1), Some(&6)));
+    assert_eq!(
+        format!("{:?}", cursor),
+        "Cursor([1, 2, 3, 4, 5, 6], Some(4))"
+    );
+    let copy = cursor.clone();
+    cursor.move_next();
+    assert_eq!(copy.current(), Some(&5));
+    assert_eq!(cursor.as_list().len(), 6);
+
+    let mut m = LinkedList::from([1u32, 2, 3, 4, 5, 6]);
+    let mut cursor = m.cursor_front_mut();
+    assert_eq!(cursor.current(), Some(&mut 1));
+    assert_eq!(cursor.peek_next(), Some(&mut 2));
+    assert_eq!(cursor.peek_prev(), None);
+    assert_eq!(cursor.index(), Some(0));
+    cursor.move_prev();
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.peek_next(), Some(&mut 1));
+    assert_eq!(cursor.peek_prev(), Some(&mut 6));
+    assert_eq!(cursor.index(), None);
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.current(), Some(&mut 2));
+    assert_eq!(cursor.peek_next(), Some(&mut 3));
+    assert_eq!(cursor.peek_prev(), Some(&mut 1));
+    assert_eq!(cursor.index(), Some(1));
+    let mut cursor2 = cursor.as_cursor();
+    assert_eq!(cursor2.current(), Some(&2));
+    assert_eq!(cursor2.index(), Some(1));
+    cursor2.move_next();
+    assert_eq!(cursor2.current(), Some(&3));
+    assert_eq!(cursor2.index(), Some(2));
+    assert_eq!(cursor.current(), Some(&mut 2));
+    assert_eq!(cursor.index(), Some(1));
+
+    let mut cursor = m.cursor_back_mut();
+    assert_eq!(cursor.current(), Some(&mut 6));
+    assert_eq!(cursor.peek_next(), None);
+    assert_eq!(cursor.peek_prev(), Some(&mut 5));
+    assert_eq!(cursor.index(), Some(5));
+    cursor.move_next();
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.peek_next(), Some(&mut 1));
+    assert_eq!(cursor.peek_prev(), Some(&mut 6));
+    assert_eq!(cursor.index(), None);
+    cursor.move_prev();
+    cursor.move_prev();
+    assert_eq!(cursor.current(), Some(&mut 5));
+    assert_eq!(cursor.peek_next(), Some(&mut 6));
+    assert_eq!(cursor.peek_prev(), Some(&mut 4));
+    assert_eq!(cursor.index(), Some(4));
+    *cursor.peek_prev().unwrap() = 40;
+    assert_eq!(
+        format!("{:?}", cursor),
+        "CursorMut([1, 2, 3, 40, 5, 6], Some(4))"
+    );
+
+    // an empty list only has the ghost
+    let empty = LinkedList::<u32>::new();
+    let mut cursor = empty.cursor_back();
+    assert_eq!((cursor.current(), cursor.index()), (None, None));
+    cursor.move_next();
+    cursor.move_prev();
+    assert_eq!((cursor.peek_next(), cursor.peek_prev()), (None, None));
+}
+
+#[test]
+fn cursor_mut_insert() {
+    let mut m = LinkedList::from([1u32, 2, 3, 4, 5, 6]);
+    let mut cursor = m.cursor_front_mut();
+    cursor.insert_before(7);
+    cursor.insert_after(8);
+    assert_eq!(cursor.index(), Some(1));
+    check_links(&m);
+    assert_eq!(values(&m), &[7, 1, 8, 2, 3, 4, 5, 6]);
+
+    let mut cursor = m.cursor_front_mut();
+    cursor.move_prev();
+    cursor.insert_before(9);
+    cursor.insert_after(10);
+    assert_eq!(cursor.index(), None);
+    cursor.move_next();
+    assert_eq!(cursor.index(), Some(0));
+    check_links(&m);
+    assert_eq!(values(&m), &[10, 7, 1, 8, 2, 3, 4, 5, 6, 9]);
+
+    let mut cursor = m.cursor_front_mut();
+    cursor.move_prev();
+    assert_eq!(cursor.remove_current(), None);
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.remove_current(), Some(7));
+    cursor.move_prev();
+    cursor.move_prev();
+    cursor.move_prev();
+    assert_eq!(cursor.remove_current(), Some(9));
+    cursor.move_next();
+    assert_eq!(cursor.remove_current(), Some(10));
+    check_links(&m);
+    assert_eq!(values(&m), &[1, 8, 2, 3, 4, 5, 6]);
+
+    let mut cursor = m.cursor_front_mut();
+    let mut p = cursor.remove_current_as_list().unwrap();
+    assert_eq!(values(&p), &[1]);
+    p.push_back(1);
+    cursor.splice_before(p);
+    assert_eq!(cursor.index(), Some(2));
+    check_links(&m);
+    assert_eq!(values(&m), &[1, 1, 8, 2, 3, 4, 5, 6]);
+    m.remove(0);
+
+    let mut cursor = m.cursor_front_mut();
+    let p = LinkedList::from([100, 101, 102, 103]);
+    let q = LinkedList::from([200, 201, 202, 203]);
+    cursor.splice_after(p);
+    cursor.splice_before(q);
+    assert_eq!(cursor.current(), Some(&mut 1));
+    assert_eq!(cursor.index(), Some(4));
+    check_links(&m);
+    assert_eq!(
+        values(&m),
+        &[200, 201, 202, 203, 1, 100, 101, 102, 103, 8, 2, 3, 4, 5, 6]
+    );
+
+    let mut cursor = m.cursor_front_mut();
+    cursor.move_prev();
+    let tmp = cursor.split_before();
+    assert_eq!(cursor.index(), None);
+    assert_eq!(values(&m), &[]);
+    m = tmp;
+    let mut cursor = m.cursor_front_mut();
+    for _ in 0..6 {
+        cursor.move_next();
+    }
+    let tmp = cursor.split_after();
+    assert_eq!(cursor.index(), Some(6));
+    check_links(&tmp);
+    assert_eq!(values(&tmp), &[102, 103, 8, 2, 3, 4, 5, 6]);
+    check_links(&m);
+    assert_eq!(values(&m), &[200, 201, 202, 203, 1, 100, 101]);
+
+    let mut cursor = m.cursor_back_mut();
+    cursor.move_prev();
+    let tmp = cursor.split_before();
+    assert_eq!(cursor.index(), Some(0));
+    assert_eq!(values(&tmp), &[200, 201, 202, 203, 1]);
+    check_links(&m);
+    assert_eq!(values(&m), &[100, 101]);
+
+    // splitting at the ends cuts off nothing
+    let mut cursor = m.cursor_front_mut();
+    assert!(cursor.split_before().is_empty());
+    cursor.move_next();
+    assert!(cursor.split_after().is_empty());
+    assert_eq!(values(&m), &[100, 101]);
+}
+
+#[test]
+fn cursor_push_pop() {
+    let mut m = LinkedList::from([1u32, 2, 3, 4, 5, 6]);
+    let mut c = m.cursor_front_mut();
+    c.move_next();
+    c.push_front(7);
+    c.push_back(8);
+    assert_eq!(c.current(), Some(&mut 2));
+    assert_eq!(c.index(), Some(2));
+    assert_eq!((c.front(), c.back()), (Some(&7), Some(&8)));
+    *c.front_mut().unwrap() = 70;
+    *c.back_mut().unwrap() = 80;
+    c.move_prev();
+    c.move_prev();
+    assert_eq!(c.current(), Some(&mut 70));
+    c.move_prev();
+    // from the ghost, pushing at the back moves the ghost's index
+    c.push_back(9);
+    c.push_front(0);
+    assert_eq!(c.current(), None);
+    c.move_prev();
+    assert_eq!(c.current(), Some(&mut 9));
+    assert_eq!(c.index(), Some(9));
+    check_links(&m);
+    assert_eq!(values(&m), &[0, 70, 1, 2, 3, 4, 5, 6, 80, 9]);
+
+    let mut m = LinkedList::from([1u32, 2, 3, 4, 5, 6]);
+    let mut c = m.cursor_back_mut();
+    c.move_prev();
+    assert_eq!(c.pop_front(), Some(1));
+    assert_eq!(c.current(), Some(&mut 5));
+    assert_eq!(c.index(), Some(3));
+    c.move_prev();
+    c.move_prev();
+    c.move_prev();
+    // the cursor was on the front, it moves on
+    assert_eq!(c.pop_front(), Some(2));
+    assert_eq!(c.current(), Some(&mut 3));
+    assert_eq!(c.index(), Some(0));
+    assert_eq!(c.pop_back(), Some(6));
+    assert_eq!(c.current(), Some(&mut 3));
+    assert_eq!(c.index(), Some(0));
+    c.move_next();
+    c.move_next();
+    // the cursor was on the back, it goes to the ghost
+    assert_eq!(c.pop_back(), Some(5));
+    assert_eq!(c.current(), None);
+    assert_eq!(c.index(), None);
+    c.move_next();
+    assert_eq!(c.index(), Some(0));
+    c.move_prev();
+    assert_eq!(c.pop_back(), Some(4));
+    c.move_next();
+    assert_eq!(c.pop_front(), Some(3));
+    assert_eq!(c.current(), None);
+    assert_eq!(c.index(), None);
+    assert_eq!((c.pop_front(), c.pop_back()), (None, None));
+    assert!(m.is_empty());
+    check_links(&m);
+}
@@ -22,9 +22,12 @@
 
 // Version 2: use raw pointer only
 
+use std::iter::FusedIterator;
+
 pub struct List<T> {
     head: Link<T>,
     tail: Link<T>,
+    len: usize,
 }
 type Link<T> = *mut Node<T>;
 
@@ -38,13 +41,14 @@ impl<T> List<T> {
         List {
             head: std::ptr::null_mut(),
             tail: std::ptr::null_mut(),
+            len: 0,
         }
     }
 
     pub fn push(&mut self, elem: T) {
         unsafe {
             let new_tail = Box::into_raw(Box::new(Node {
-                elem: elem,
+                elem,
                 next: std::ptr::null_mut(),
             }));
 
@@ -57,6 +61,7 @@ impl<T> List<T> {
 
             self.tail = new_tail;
         }
+        self.len += 1;
     }
 
     pub fn pop(&mut self) -> Option<T> {
@@ -73,22 +78,44 @@ impl<T> List<T> {
                 self.tail = std::ptr::null_mut();
             }
 
+            self.len -= 1;
             return Some(Box::from_raw(old_head).elem);
         }
     }
 
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
     pub fn peek(&self) -> Option<&T> {
         unsafe { self.head.as_ref().map(|node| &node.elem) }
     }
 
-    pub fn peek_mut(&self) -> Option<&mut T> {
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
         unsafe { self.head.as_mut().map(|node| &mut node.elem) }
     }
 }
 
 impl<T> Drop for List<T> {
     fn drop(&mut self) {
-        while let Some(_) = self.pop() {}
+        // if dropping an element panics, keep popping the rest while unwinding,
+        // otherwise the remaining nodes (raw pointers) would leak
+        struct DropGuard<'a, T>(&'a mut List<T>);
+        impl<'a, T> Drop for DropGuard<'a, T> {
+            fn drop(&mut self) {
+                while self.0.pop().is_some() {}
+            }
+        }
+
+        while let Some(elem) = self.pop() {
+            let guard = DropGuard(self);
+            drop(elem);
+            std::mem::forget(guard);
+        }
     }
 }
 
@@ -96,10 +123,12 @@ pub struct IntoIter<T>(List<T>);
 
 pub struct Iter<'a, T> {
     next: Option<&'a Node<T>>,
+    len: usize,
 }
 
 pub struct IterMut<'a, T> {
     next: Option<&'a mut Node<T>>,
+    len: usize,
 }
 
 // create iterators
@@ -111,13 +140,15 @@ impl<T> List<T> {
         unsafe {
             Iter {
                 next: self.head.as_ref(),
+                len: self.len,
             }
         }
     }
-    pub fn iter_mut(&self) -> IterMut<'_, T> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         unsafe {
             IterMut {
                 next: self.head.as_mut(),
+                len: self.len,
             }
         }
     }
@@ -129,6 +160,10 @@ impl<T> Iterator for IntoIter<T> {
     fn next(&mut self) -> Option<Self::Item> {
         self.0.pop()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
 }
 
 impl<'a, T> Iterator for Iter<'a, T> {
@@ -139,9 +174,14 @@ impl<'a, T> Iterator for Iter<'a, T> {
             unsafe {
                 self.next = node.next.as_ref();
             }
+            self.len -= 1;
             &node.elem
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
 }
 
 impl<'a, T> Iterator for IterMut<'a, T> {
@@ -151,14 +191,100 @@ impl<'a, T> Iterator for IterMut<'a, T> {
             unsafe {
                 self.next = node.next.as_mut();
             }
+            self.len -= 1;
             &mut node.elem
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+// size_hint is exact, and once exhausted they keep returning None
+impl<T> ExactSizeIterator for IntoIter<T> {}
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+impl<'a, T> FusedIterator for IterMut<'a, T> {}
+
+//////////////////////
+// other interfaces //
+//////////////////////
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: std::hash::Hash> std::hash::Hash for List<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for item in self.iter() {
+            item.hash(state);
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Clone> Clone for List<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> Extend<T> for List<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a> Extend<&'a T> for List<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T: PartialEq> PartialEq for List<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+impl<T: Eq> Eq for List<T> {}
+
+impl<T: PartialOrd> PartialOrd for List<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+impl<T: Ord> Ord for List<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.iter().cmp(other.iter())
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::List;
+    use crate::test_helpers::{panics, DropCounter};
     #[test]
     fn basics() {
         let mut list = List::new();
@@ -240,6 +366,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::option_map_unit_fn)]
     fn miri_food() {
         let mut list = List::new();
 
@@ -276,4 +403,112 @@ mod test {
 
         // Drop it on the ground and let the dtor exercise itself
     }
+
+    #[test]
+    fn drop_order() {
+        let counter = DropCounter::new();
+        let mut list = List::new();
+        for id in 0..5 {
+            list.push(counter.track(id));
+        }
+        assert_eq!(counter.count(), 0);
+
+        drop(list);
+        assert_eq!(counter.dropped(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn drop_panic() {
+        let counter = DropCounter::new();
+        let mut list = List::new();
+        list.push(counter.track(0));
+        list.push(counter.track(1));
+        list.push(counter.panic_on_drop(2));
+        list.push(counter.track(3));
+        list.push(counter.track(4));
+
+        // the nodes after the panicking one are still dropped, exactly once
+        assert!(panics(move || drop(list)));
+        assert_eq!(counter.dropped(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn len() {
+        let mut list = List::new();
+        assert!(list.is_empty());
+        assert_eq!(list.len(), 0);
+
+        list.push(1);
+        list.push(2);
+        list.push(3);
+        assert_eq!(list.len(), 3);
+        list.pop();
+        assert_eq!(list.len(), 2);
+        list.pop();
+        list.pop();
+        assert!(list.is_empty());
+        assert_eq!(list.pop(), None);
+        assert_eq!(list.len(), 0);
+    }
+
+    #[test]
+    fn exact_size() {
+        let mut list = List::new();
+        list.push(1);
+        list.push(2);
+        list.push(3);
+
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 3);
+        iter.next();
+        assert_eq!(iter.size_hint(), (2, Some(2)));
+        iter.next();
+        iter.next();
+        assert_eq!(iter.len(), 0);
+        // fused: keeps returning None
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+
+        let mut iter = list.iter_mut();
+        iter.next();
+        assert_eq!(iter.len(), 2);
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.len(), 3);
+        iter.next();
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn traits() {
+        let empty: List<i32> = Default::default();
+        assert!(empty.is_empty());
+
+        let a: List<i32> = (1..=3).collect();
+        assert_eq!(format!("{:?}", a), "[1, 2, 3]");
+
+        let mut b = List::new();
+        b.extend(vec![1, 2]);
+        b.extend(&[3]);
+        assert_eq!(a, b);
+        assert_eq!(a.clone(), a);
+
+        b.pop();
+        assert_ne!(a, b);
+        assert!(a < b);
+        assert!(b > a);
+        assert!(empty < a);
+        assert_eq!(a.cmp(&a), std::cmp::Ordering::Equal);
+
+        let nan: List<f64> = [f64::NAN].into_iter().collect();
+        assert_eq!(nan.partial_cmp(&nan), None);
+
+        let mut map = std::collections::HashMap::new();
+        map.insert(a.clone(), "a");
+        map.insert(b.clone(), "b");
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&a), Some(&"a"));
+        assert_eq!(map.get(&b), Some(&"b"));
+    }
 }
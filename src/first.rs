@@ -3,42 +3,77 @@
 //   2) Use mem::replace to take ownership of a &mut ref
 //   3) Use cfg(test) to suppress compile warning for unit test
 //   4) Implement iterative "Drop" to avoid recursive call (potential stack overflow)
+//   5) Without Option, we borrow the enum's content by matching on a reference:
+//      `match &self.head { Link::More(node) => ..., Link::Empty => ... }`
 
 use std::mem;
 
-pub struct List {
-    head: Link,
+pub struct List<T> {
+    head: Link<T>,
+    len: usize,
 }
 
+// the list used to hold i32 only, keep the old type around for existing users
+pub type IntList = List<i32>;
+
 // node is a struct of value + pointer to next
-struct Node {
-    value: i32,
-    next: Link,
+struct Node<T> {
+    value: T,
+    next: Link<T>,
 }
 
 // we can substitute it with Option<Node>
 // next item can either be empty, or a node
-enum Link {
+enum Link<T> {
     Empty,
-    More(Box<Node>),
+    More(Box<Node<T>>),
+}
+
+impl<T> Link<T> {
+    // what Option::as_deref() would give us
+    fn as_node(&self) -> Option<&Node<T>> {
+        match self {
+            Link::Empty => {
+                return None;
+            }
+            Link::More(node) => {
+                return Some(node);
+            }
+        }
+    }
+
+    fn as_node_mut(&mut self) -> Option<&mut Node<T>> {
+        match self {
+            Link::Empty => {
+                return None;
+            }
+            Link::More(node) => {
+                return Some(node);
+            }
+        }
+    }
 }
 
-impl List {
+impl<T> List<T> {
     pub fn new() -> Self {
-        return List { head: Link::Empty };
+        return List {
+            head: Link::Empty,
+            len: 0,
+        };
     }
 
     // a stack
-    pub fn push(&mut self, elem: i32) {
+    pub fn push(&mut self, elem: T) {
         let node = Box::new(Node {
             value: elem,
             next: mem::replace(&mut self.head, Link::Empty),
         });
 
         self.head = Link::More(node);
+        self.len += 1;
     }
 
-    pub fn pop(&mut self) -> Option<i32> {
+    pub fn pop(&mut self) -> Option<T> {
         let head = mem::replace(&mut self.head, Link::Empty);
         match head {
             Link::Empty => {
@@ -46,38 +81,167 @@ impl List {
             }
             Link::More(node) => {
                 self.head = node.next;
+                self.len -= 1;
                 return Some(node.value);
             }
         }
     }
+
+    pub fn peek(&self) -> Option<&T> {
+        return self.head.as_node().map(|node| &node.value);
+    }
+
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        return self.head.as_node_mut().map(|node| &mut node.value);
+    }
+
+    pub fn len(&self) -> usize {
+        return self.len;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.len == 0;
+    }
 }
 
 // we have to implement Drop in an iterative manner
 // to avoid recursive call blowing the stack
-impl Drop for List {
+impl<T> Drop for List<T> {
     fn drop(&mut self) {
-        let mut cur_link = mem::replace(&mut self.head, Link::Empty);
-        loop {
-            match cur_link {
-                Link::Empty => {
-                    // all nodes dropped
-                    break;
-                }
-
-                Link::More(mut node) => {
-                    // it does two things:
-                    //   1) remove ownership to the next node by replacing next with Empty
-                    //   2) assign next node to current node
-                    cur_link = mem::replace(&mut node.next, Link::Empty);
-                }
+        // `pop` moves the next node into `head` before the popped one goes
+        // away, so no node is ever dropped along with the rest of the chain.
+        // if dropping an element panics, keep popping the rest while unwinding,
+        // a chain left in a local would be dropped recursively and blow the stack
+        struct DropGuard<'a, T>(&'a mut List<T>);
+        impl<'a, T> Drop for DropGuard<'a, T> {
+            fn drop(&mut self) {
+                while self.0.pop().is_some() {}
             }
         }
 
-        // more elegant:
-        // `while let` == "do this thing until this pattern doesn't match"
-        // while let Link::More(mut node) = cur_link {
-        //     cur_link = mem::replace(&mut node.next, Link::Empty);
-        // }
+        while let Some(elem) = self.pop() {
+            let guard = DropGuard(self);
+            drop(elem);
+            mem::forget(guard);
+        }
+    }
+}
+
+// take the list itself
+pub struct IntoIter<T>(List<T>);
+// const ref
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+    len: usize,
+}
+pub struct IterMut<'a, T> {
+    next: Option<&'a mut Node<T>>,
+    len: usize,
+}
+
+// iterators, from the top of the stack to the bottom
+impl<T> List<T> {
+    pub fn into_iter(self) -> IntoIter<T> {
+        return IntoIter(self);
+    }
+    pub fn iter(&self) -> Iter<'_, T> {
+        return Iter {
+            next: self.head.as_node(),
+            len: self.len,
+        };
+    }
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        return IterMut {
+            next: self.head.as_node_mut(),
+            len: self.len,
+        };
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.0.pop();
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return (self.0.len, Some(self.0.len));
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.next.map(|node| {
+            self.next = node.next.as_node();
+            self.len -= 1;
+            return &node.value;
+        });
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return (self.len, Some(self.len));
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.next.take().map(|node| {
+            self.next = node.next.as_node_mut();
+            self.len -= 1;
+            return &mut node.value;
+        });
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return (self.len, Some(self.len));
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+// other interfaces
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return f.debug_list().entries(self.iter()).finish();
+    }
+}
+
+impl<T: Clone> Clone for List<T> {
+    fn clone(&self) -> Self {
+        return self.iter().cloned().collect();
+    }
+}
+
+// keeps the order of the iterator: the first item ends up on top,
+// so `list.iter().collect::<List<_>>()` gives the same stack back
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = List::new();
+        // append at the bottom, through a &mut to the last (empty) link
+        let mut tail = &mut list.head;
+        for elem in iter {
+            *tail = Link::More(Box::new(Node {
+                value: elem,
+                next: Link::Empty,
+            }));
+            list.len += 1;
+            tail = match tail {
+                Link::More(node) => &mut node.next,
+                Link::Empty => unreachable!(),
+            };
+        }
+        return list;
     }
 }
 
@@ -86,7 +250,8 @@ impl Drop for List {
 // only compile when running tests (cargo test), to avoid unused warning
 #[cfg(test)]
 mod first_list_tests {
-    use super::List;
+    use super::{IntList, List};
+    use crate::test_helpers::{panics, DropCounter};
 
     #[test]
     fn push_and_pop() {
@@ -116,4 +281,127 @@ mod first_list_tests {
         assert_eq!(list.pop(), Some(1));
         assert_eq!(list.pop(), None);
     }
+
+    #[test]
+    fn drop_long_list() {
+        // the iterative Drop must not blow the stack
+        let mut list = List::new();
+        for i in 0..1_000_000 {
+            list.push(i);
+        }
+        drop(list);
+    }
+
+    #[test]
+    fn int_list() {
+        // the pre-generic type still works
+        let mut list = IntList::new();
+        list.push(1);
+        assert_eq!(list.pop(), Some(1));
+    }
+
+    #[test]
+    fn peek_and_len() {
+        let mut list = List::new();
+        assert!(list.is_empty());
+        assert_eq!(list.peek(), None);
+        assert_eq!(list.peek_mut(), None);
+
+        list.push(String::from("a"));
+        list.push(String::from("b"));
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.peek().map(|s| s.as_str()), Some("b"));
+
+        if let Some(value) = list.peek_mut() {
+            value.push('!');
+        }
+        assert_eq!(list.pop().as_deref(), Some("b!"));
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.pop().as_deref(), Some("a"));
+        assert_eq!(list.pop(), None);
+        assert_eq!(list.len(), 0);
+    }
+
+    #[test]
+    fn iterators() {
+        let mut list = List::new();
+        list.push(1);
+        list.push(2);
+        list.push(3);
+
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next(), None);
+
+        for value in list.iter_mut() {
+            *value *= 10;
+        }
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next(), Some(30));
+        assert_eq!(iter.next(), Some(20));
+        assert_eq!(iter.next(), Some(10));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn traits() {
+        // the first item of the iterator is the top of the stack
+        let list: List<_> = (1..=3).collect();
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.peek(), Some(&1));
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+
+        let mut copy = list.clone();
+        assert_eq!(format!("{:?}", copy), "[1, 2, 3]");
+        copy.push(0);
+        assert_eq!(copy.len(), 4);
+        assert_eq!(list.len(), 3);
+    }
+
+    #[test]
+    fn drop_order() {
+        let counter = DropCounter::new();
+        let mut list = List::new();
+        for id in 0..5 {
+            list.push(counter.track(id));
+        }
+        assert_eq!(counter.count(), 0);
+
+        drop(list);
+        assert_eq!(counter.dropped(), vec![4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn drop_panic() {
+        let counter = DropCounter::new();
+        let mut list = List::new();
+        list.push(counter.track(0));
+        list.push(counter.panic_on_drop(1));
+        list.push(counter.track(2));
+
+        // the nodes after the panicking one are still dropped, exactly once
+        assert!(panics(move || drop(list)));
+        assert_eq!(counter.dropped(), vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn drop_panic_long() {
+        // the panic comes first, the million nodes behind it must still be
+        // dropped one by one and not recursively
+        let counter = DropCounter::new();
+        let mut list = List::new();
+        for id in 0..1_000_000 {
+            list.push(counter.track(id));
+        }
+        list.push(counter.panic_on_drop(1_000_000));
+
+        assert!(panics(move || drop(list)));
+        assert_eq!(counter.count(), 1_000_001);
+    }
 }
@@ -10,27 +10,34 @@
 //      Rc<RefCell<T>> to reintroduce mutability
 //   4. Only two operation matters in RefCell, borrow() and borrow_mut()
 //   5. RefCell<T> is only for single-threaded scenarios, use Mutex<T> for multi-threaded situation
-//   6. Rc<T> can't release if it holds strong ref to each other,
-//      so we need to manually pop elements in Drop
+//   6. Rc<T> can't release if it holds strong ref to each other, so the back links are Weak<T>.
+//      Only the next links own nodes, and a stray Rc to one node can't keep the others alive
 //   7. When we borrow from a RefCell, we get a Ref<T> type (instead of &T), which is a reference with lifetime
 //      This is how it implements dynamic borrow checking.
 //   8. A deque can iterate from both front and back, we need to implement both next() and next_back()
 //   9. There is no easy way to implement Iter and IterMut with RefCell
+//  10. A cursor can still walk the list by holding a Weak to the current node, and borrowing
+//      the list mutably for as long as the cursor lives keeps anyone else from unlinking it.
+//      Handing out a Ref through that Weak takes one line of unsafe, safe Rust can't say
+//      "the list keeps this node alive"
 
 use std::cell::{Ref, RefCell, RefMut};
-use std::rc::Rc;
+use std::iter::FusedIterator;
+use std::rc::{Rc, Weak};
 
 type Link<T> = Option<Rc<RefCell<Node<T>>>>;
+type WeakLink<T> = Option<Weak<RefCell<Node<T>>>>;
 
 pub struct List<T> {
     head: Link<T>,
     tail: Link<T>,
+    len: usize,
 }
 
 struct Node<T> {
     elem: T,
     next: Link<T>,
-    prev: Link<T>,
+    prev: WeakLink<T>,
 }
 
 impl<T> List<T> {
@@ -38,6 +45,7 @@ impl<T> List<T> {
         List {
             head: None,
             tail: None,
+            len: 0,
         }
     }
 
@@ -45,7 +53,7 @@ impl<T> List<T> {
         let new_head = Node::new(elem);
         match self.head.take() {
             Some(old_head) => {
-                old_head.borrow_mut().prev = Some(Rc::clone(&new_head));
+                old_head.borrow_mut().prev = Some(Rc::downgrade(&new_head));
                 new_head.borrow_mut().next = Some(old_head);
                 self.head = Some(new_head);
             }
@@ -54,6 +62,7 @@ impl<T> List<T> {
                 self.tail = Some(new_head);
             }
         }
+        self.len += 1;
     }
 
     pub fn push_back(&mut self, elem: T) {
@@ -61,7 +70,7 @@ impl<T> List<T> {
         match self.tail.take() {
             Some(old_tail) => {
                 old_tail.borrow_mut().next = Some(Rc::clone(&new_tail));
-                new_tail.borrow_mut().prev = Some(old_tail);
+                new_tail.borrow_mut().prev = Some(Rc::downgrade(&old_tail));
                 self.tail = Some(new_tail);
             }
             None => {
@@ -69,6 +78,7 @@ impl<T> List<T> {
                 self.head = Some(new_tail);
             }
         }
+        self.len += 1;
     }
 
     pub fn pop_front(&mut self) -> Option<T> {
@@ -83,13 +93,19 @@ impl<T> List<T> {
                     self.head = Some(new_head);
                 }
             }
+            self.len -= 1;
             return Rc::try_unwrap(old_head).ok().unwrap().into_inner().elem;
         });
     }
 
     pub fn pop_back(&mut self) -> Option<T> {
         return self.tail.take().map(|old_tail| {
-            match old_tail.borrow_mut().prev.take() {
+            match old_tail
+                .borrow_mut()
+                .prev
+                .take()
+                .and_then(|prev| prev.upgrade())
+            {
                 None => {
                     // reset head
                     self.head = None;
@@ -99,29 +115,38 @@ impl<T> List<T> {
                     self.tail = Some(new_tail);
                 }
             }
+            self.len -= 1;
             return Rc::try_unwrap(old_tail).ok().unwrap().into_inner().elem;
         });
     }
 
-    pub fn peek_front(&self) -> Option<Ref<T>> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn peek_front(&self) -> Option<Ref<'_, T>> {
         self.head
             .as_ref()
             .map(|node| Ref::map(node.borrow(), |borrowed| &borrowed.elem))
     }
 
-    pub fn peek_front_mut(&self) -> Option<RefMut<T>> {
+    pub fn peek_front_mut(&self) -> Option<RefMut<'_, T>> {
         self.head
             .as_ref()
             .map(|node| RefMut::map(node.borrow_mut(), |borrowed| &mut borrowed.elem))
     }
 
-    pub fn peek_back(&self) -> Option<Ref<T>> {
+    pub fn peek_back(&self) -> Option<Ref<'_, T>> {
         self.tail
             .as_ref()
             .map(|node| Ref::map(node.borrow(), |borrowed| &borrowed.elem))
     }
 
-    pub fn peek_back_mut(&self) -> Option<RefMut<T>> {
+    pub fn peek_back_mut(&self) -> Option<RefMut<'_, T>> {
         self.tail
             .as_ref()
             .map(|node| RefMut::map(node.borrow_mut(), |borrowed| &mut borrowed.elem))
@@ -131,16 +156,46 @@ impl<T> List<T> {
 impl<T> Node<T> {
     pub fn new(elem: T) -> Rc<RefCell<Self>> {
         Rc::new(RefCell::new(Node {
-            elem: elem,
+            elem,
             prev: None,
             next: None,
         }))
     }
 }
 
+impl<T> List<T> {
+    // Takes the head node out without unwrapping it, its `next` becomes the
+    // head. A Weak pins the allocation of the node it points to, so the new
+    // head forgets its `prev` in case someone else still holds the old one.
+    fn unlink_front(&mut self) -> Link<T> {
+        let old_head = self.head.take()?;
+        self.head = old_head.borrow_mut().next.take();
+        if let Some(new_head) = &self.head {
+            new_head.borrow_mut().prev = None;
+        }
+        Some(old_head)
+    }
+}
+
 impl<T> Drop for List<T> {
     fn drop(&mut self) {
-        while self.pop_front().is_some() {}
+        // no cycles to break, but unlink the nodes one by one so dropping a long
+        // list doesn't recurse. If an element panics, the guard keeps unlinking
+        // the rest the same way while unwinding.
+        struct DropGuard<'a, T>(&'a mut List<T>);
+
+        impl<'a, T> Drop for DropGuard<'a, T> {
+            fn drop(&mut self) {
+                while self.0.unlink_front().is_some() {}
+            }
+        }
+
+        self.tail = None;
+        while let Some(node) = self.unlink_front() {
+            let guard = DropGuard(self);
+            drop(node);
+            std::mem::forget(guard);
+        }
     }
 }
 
@@ -159,6 +214,10 @@ impl<T> Iterator for IntoIter<T> {
     fn next(&mut self) -> Option<Self::Item> {
         self.0.pop_front()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
 }
 // iterator from back
 impl<T> DoubleEndedIterator for IntoIter<T> {
@@ -167,6 +226,289 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
     }
 }
 
+impl<T> ExactSizeIterator for IntoIter<T> {}
+impl<T> FusedIterator for IntoIter<T> {}
+
+// mutable cursor, sits on a node or on the "ghost" position between tail and head.
+// It only holds a Weak to the node, the list keeps owning it.
+pub struct CursorMut<'a, T> {
+    list: &'a mut List<T>,
+    cur: WeakLink<T>,
+    index: Option<usize>,
+}
+
+impl<T> List<T> {
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        let cur = self.head.as_ref().map(Rc::downgrade);
+        let index = cur.as_ref().map(|_| 0);
+        CursorMut {
+            list: self,
+            cur,
+            index,
+        }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        let cur = self.tail.as_ref().map(Rc::downgrade);
+        let index = cur.as_ref().map(|_| self.len - 1);
+        CursorMut {
+            list: self,
+            cur,
+            index,
+        }
+    }
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    // None on the ghost
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    // The list owns every node and is borrowed mutably by the cursor, and only
+    // the cursor's own `&mut self` methods unlink nodes, so `cur` never dangles
+    // while `self` is borrowed
+    fn current(&self) -> Link<T> {
+        self.cur.as_ref().and_then(Weak::upgrade)
+    }
+
+    // moving past either end lands on the ghost, moving again wraps around
+    pub fn move_next(&mut self) {
+        match self.current() {
+            Some(cur) => {
+                self.cur = cur.borrow().next.as_ref().map(Rc::downgrade);
+                self.index = match self.cur {
+                    Some(_) => self.index.map(|index| index + 1),
+                    None => None,
+                };
+            }
+            None => {
+                self.cur = self.list.head.as_ref().map(Rc::downgrade);
+                self.index = self.cur.as_ref().map(|_| 0);
+            }
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        match self.current() {
+            Some(cur) => {
+                self.cur = cur.borrow().prev.clone();
+                self.index = match self.cur {
+                    Some(_) => self.index.map(|index| index - 1),
+                    None => None,
+                };
+            }
+            None => {
+                self.cur = self.list.tail.as_ref().map(Rc::downgrade);
+                self.index = self.cur.as_ref().map(|_| self.list.len - 1);
+            }
+        }
+    }
+
+    // A Ref can't borrow from an Rc we upgraded just for this call, so it
+    // borrows the RefCell through the Weak instead. That's sound for the
+    // reason given on `current`: the node outlives any borrow of `self`.
+    pub fn peek(&self) -> Option<Ref<'_, T>> {
+        self.cur.as_ref().map(|node| {
+            let node = unsafe { &*node.as_ptr() };
+            Ref::map(node.borrow(), |borrowed| &borrowed.elem)
+        })
+    }
+
+    pub fn peek_mut(&mut self) -> Option<RefMut<'_, T>> {
+        self.cur.as_ref().map(|node| {
+            let node = unsafe { &*node.as_ptr() };
+            RefMut::map(node.borrow_mut(), |borrowed| &mut borrowed.elem)
+        })
+    }
+
+    // on the ghost this pushes to the back
+    pub fn insert_before(&mut self, elem: T) {
+        let cur = match self.current() {
+            Some(cur) => cur,
+            None => return self.list.push_back(elem),
+        };
+        let new = Node::new(elem);
+        match cur.borrow_mut().prev.take().and_then(|prev| prev.upgrade()) {
+            Some(prev) => {
+                prev.borrow_mut().next = Some(Rc::clone(&new));
+                new.borrow_mut().prev = Some(Rc::downgrade(&prev));
+            }
+            None => self.list.head = Some(Rc::clone(&new)),
+        }
+        cur.borrow_mut().prev = Some(Rc::downgrade(&new));
+        new.borrow_mut().next = Some(cur);
+        self.list.len += 1;
+        self.index = self.index.map(|index| index + 1);
+    }
+
+    // on the ghost this pushes to the front
+    pub fn insert_after(&mut self, elem: T) {
+        let cur = match self.current() {
+            Some(cur) => cur,
+            None => return self.list.push_front(elem),
+        };
+        let new = Node::new(elem);
+        match cur.borrow_mut().next.take() {
+            Some(next) => {
+                next.borrow_mut().prev = Some(Rc::downgrade(&new));
+                new.borrow_mut().next = Some(next);
+            }
+            None => self.list.tail = Some(Rc::clone(&new)),
+        }
+        new.borrow_mut().prev = Some(Rc::downgrade(&cur));
+        cur.borrow_mut().next = Some(new);
+        self.list.len += 1;
+    }
+
+    // removes the current element and moves to the next one
+    pub fn remove(&mut self) -> Option<T> {
+        let old = self.current()?;
+        let prev = old.borrow_mut().prev.take().and_then(|prev| prev.upgrade());
+        let next = old.borrow_mut().next.take();
+        match &prev {
+            Some(prev) => prev.borrow_mut().next = next.clone(),
+            None => self.list.head = next.clone(),
+        }
+        match &next {
+            Some(next) => next.borrow_mut().prev = prev.as_ref().map(Rc::downgrade),
+            None => {
+                self.list.tail = prev;
+                self.index = None;
+            }
+        }
+        self.cur = next.as_ref().map(Rc::downgrade);
+        self.list.len -= 1;
+        // unlinked, so `old` is the last Rc to the node
+        return Some(Rc::try_unwrap(old).ok().unwrap().into_inner().elem);
+    }
+}
+
+//////////////////////
+// other interfaces //
+//////////////////////
+
+// We can't hand out an Iter (see below), but inside the module we can walk
+// the nodes by cloning the Rc of each one and borrowing it for a moment.
+struct Nodes<T>(Link<T>);
+
+impl<T> Iterator for Nodes<T> {
+    type Item = Rc<RefCell<Node<T>>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.take().inspect(|node| {
+            self.0 = node.borrow().next.clone();
+        })
+    }
+}
+
+impl<T> List<T> {
+    fn nodes(&self) -> Nodes<T> {
+        Nodes(self.head.clone())
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: std::hash::Hash> std::hash::Hash for List<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for node in self.nodes() {
+            node.borrow().elem.hash(state);
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut list = f.debug_list();
+        for node in self.nodes() {
+            list.entry(&node.borrow().elem);
+        }
+        list.finish()
+    }
+}
+
+impl<T: Clone> Clone for List<T> {
+    fn clone(&self) -> Self {
+        self.nodes()
+            .map(|node| node.borrow().elem.clone())
+            .collect()
+    }
+}
+
+impl<T> Extend<T> for List<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push_back(item);
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a> Extend<&'a T> for List<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T: PartialEq> PartialEq for List<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .nodes()
+                .zip(other.nodes())
+                .all(|(a, b)| a.borrow().elem == b.borrow().elem)
+    }
+}
+impl<T: Eq> Eq for List<T> {}
+
+impl<T: PartialOrd> PartialOrd for List<T> {
+    // lexicographic, like Iterator::partial_cmp
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        let mut ours = self.nodes();
+        let mut theirs = other.nodes();
+        loop {
+            match (ours.next(), theirs.next()) {
+                (None, None) => return Some(std::cmp::Ordering::Equal),
+                (None, Some(_)) => return Some(std::cmp::Ordering::Less),
+                (Some(_), None) => return Some(std::cmp::Ordering::Greater),
+                (Some(a), Some(b)) => match a.borrow().elem.partial_cmp(&b.borrow().elem) {
+                    Some(std::cmp::Ordering::Equal) => {}
+                    not_equal => return not_equal,
+                },
+            }
+        }
+    }
+}
+impl<T: Ord> Ord for List<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let mut ours = self.nodes();
+        let mut theirs = other.nodes();
+        loop {
+            match (ours.next(), theirs.next()) {
+                (None, None) => return std::cmp::Ordering::Equal,
+                (None, Some(_)) => return std::cmp::Ordering::Less,
+                (Some(_), None) => return std::cmp::Ordering::Greater,
+                (Some(a), Some(b)) => match a.borrow().elem.cmp(&b.borrow().elem) {
+                    std::cmp::Ordering::Equal => {}
+                    not_equal => return not_equal,
+                },
+            }
+        }
+    }
+}
+
 // const ref, Iter, no elegant implementation
 // pub struct Iter<'a, T>(Option<Ref<'a, Node<T>>>);
 // impl<T> List<T> {
@@ -217,6 +559,8 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
 #[cfg(test)]
 mod test {
     use super::List;
+    use crate::test_helpers::{live_allocations, panics, DropCounter};
+    use std::rc::Weak;
 
     #[test]
     fn basics() {
@@ -305,4 +649,301 @@ mod test {
         assert_eq!(iter.next_back(), None);
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn drop_order() {
+        let counter = DropCounter::new();
+        let mut list = List::new();
+        for id in 0..5 {
+            list.push_back(counter.track(id));
+        }
+        assert_eq!(counter.count(), 0);
+
+        drop(list);
+        assert_eq!(counter.dropped(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn drop_panic() {
+        let counter = DropCounter::new();
+        let mut list = List::new();
+        list.push_back(counter.track(0));
+        list.push_back(counter.track(1));
+        list.push_back(counter.panic_on_drop(2));
+        list.push_back(counter.track(3));
+        list.push_back(counter.track(4));
+
+        // the nodes after the panicking one are still dropped, exactly once
+        assert!(panics(move || drop(list)));
+        assert_eq!(counter.dropped(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn drop_panic_long() {
+        // the panic comes first, the million nodes behind it must still be
+        // dropped one by one and not recursively
+        let counter = DropCounter::new();
+        let mut list = List::new();
+        list.push_back(counter.panic_on_drop(0));
+        for id in 1..=1_000_000 {
+            list.push_back(counter.track(id));
+        }
+        assert!(panics(move || drop(list)));
+        assert_eq!(counter.count(), 1_000_001);
+    }
+
+    #[test]
+    fn len() {
+        let mut list = List::new();
+        assert!(list.is_empty());
+
+        list.push_front(2);
+        list.push_back(3);
+        list.push_front(1);
+        assert_eq!(list.len(), 3);
+        list.pop_back();
+        assert_eq!(list.len(), 2);
+        list.pop_front();
+        list.pop_front();
+        assert!(list.is_empty());
+        assert_eq!(list.pop_back(), None);
+        assert_eq!(list.len(), 0);
+    }
+
+    #[test]
+    fn exact_size() {
+        let mut list = List::new();
+        list.push_back(1);
+        list.push_back(2);
+        list.push_back(3);
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.len(), 3);
+        iter.next_back();
+        assert_eq!(iter.size_hint(), (2, Some(2)));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    #[test]
+    fn traits() {
+        let empty: List<i32> = Default::default();
+        assert!(empty.is_empty());
+
+        let a: List<i32> = (1..=3).collect();
+        assert_eq!(format!("{:?}", a), "[1, 2, 3]");
+
+        let mut b = List::new();
+        b.extend(vec![2, 3]);
+        b.push_front(1);
+        let mut c = List::new();
+        c.extend(&[1, 2, 3]);
+        assert_eq!(a, b);
+        assert_eq!(a, c);
+        assert_eq!(a.clone(), a);
+
+        b.pop_front();
+        assert_ne!(a, b);
+        assert!(a < b);
+        assert!(b > a);
+        assert!(empty < a);
+        assert_eq!(a.cmp(&a), std::cmp::Ordering::Equal);
+        c.pop_back();
+        assert_eq!(c.cmp(&a), std::cmp::Ordering::Less);
+
+        let nan: List<f64> = [1.0, f64::NAN].into_iter().collect();
+        assert_eq!(nan.partial_cmp(&nan), None);
+
+        // the clone shares nothing with the original
+        let d = a.clone();
+        *d.peek_front_mut().unwrap() = 10;
+        assert_eq!(*a.peek_front().unwrap(), 1);
+
+        // RefCell makes it a poor map key, but equal lists still hash equally
+        use std::hash::{BuildHasher, RandomState};
+        let state = RandomState::new();
+        assert_eq!(state.hash_one(&a), state.hash_one(a.clone()));
+        assert_ne!(state.hash_one(&a), state.hash_one(&b));
+    }
+
+    // walks the links both ways, checking they agree with each other and with len
+    fn check_links<T: Clone + PartialEq + std::fmt::Debug>(list: &List<T>) -> Vec<T> {
+        let forward: Vec<T> = list
+            .nodes()
+            .map(|node| node.borrow().elem.clone())
+            .collect();
+        let mut backward = Vec::new();
+        let mut cur = list.tail.clone();
+        while let Some(node) = cur {
+            backward.push(node.borrow().elem.clone());
+            cur = node.borrow().prev.as_ref().and_then(Weak::upgrade);
+        }
+        backward.reverse();
+        assert_eq!(forward, backward);
+        assert_eq!(forward.len(), list.len());
+        forward
+    }
+
+    #[test]
+    fn cursor_move_peek() {
+        let mut list: List<i32> = (1..=3).collect();
+        let mut cursor = list.cursor_front_mut();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(*cursor.peek().unwrap(), 1);
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(1));
+        *cursor.peek_mut().unwrap() *= 10;
+        cursor.move_next();
+        cursor.move_next();
+        // the ghost
+        assert_eq!(cursor.index(), None);
+        assert!(cursor.peek().is_none());
+        assert!(cursor.peek_mut().is_none());
+        cursor.move_next();
+        assert_eq!(*cursor.peek().unwrap(), 1);
+        cursor.move_prev();
+        assert_eq!(cursor.index(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(*cursor.peek().unwrap(), 3);
+        drop(cursor);
+        assert_eq!(check_links(&list), vec![1, 20, 3]);
+
+        let mut cursor = list.cursor_back_mut();
+        assert_eq!(cursor.index(), Some(2));
+        cursor.move_prev();
+        assert_eq!(*cursor.peek().unwrap(), 20);
+
+        let mut empty: List<i32> = List::new();
+        let mut cursor = empty.cursor_front_mut();
+        assert_eq!(cursor.index(), None);
+        cursor.move_next();
+        cursor.move_prev();
+        assert_eq!(cursor.index(), None);
+        assert!(cursor.peek().is_none());
+    }
+
+    #[test]
+    fn cursor_insert() {
+        let mut list: List<i32> = List::new();
+        let mut cursor = list.cursor_front_mut();
+        // on the ghost, before is the back and after is the front
+        cursor.insert_before(3);
+        cursor.insert_after(1);
+        assert_eq!(cursor.index(), None);
+        cursor.move_next();
+        assert_eq!(*cursor.peek().unwrap(), 1);
+        cursor.insert_before(0);
+        assert_eq!(cursor.index(), Some(1));
+        cursor.insert_after(2);
+        assert_eq!(*cursor.peek().unwrap(), 1);
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(3));
+        cursor.insert_after(4);
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(4));
+        drop(cursor);
+        assert_eq!(check_links(&list), vec![0, 1, 2, 3, 4]);
+        assert_eq!(list.pop_back(), Some(4));
+        assert_eq!(list.pop_front(), Some(0));
+        assert_eq!(check_links(&list), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn cursor_remove() {
+        let mut list: List<i32> = (0..5).collect();
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.move_next();
+        // middle, the cursor moves on to the next element
+        assert_eq!(cursor.remove(), Some(2));
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(*cursor.peek().unwrap(), 3);
+        // head
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.remove(), Some(0));
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(*cursor.peek().unwrap(), 1);
+        // tail, the cursor ends up on the ghost
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.remove(), Some(4));
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.remove(), None);
+        drop(cursor);
+        assert_eq!(check_links(&list), vec![1, 3]);
+
+        let mut cursor = list.cursor_back_mut();
+        assert_eq!(cursor.remove(), Some(3));
+        cursor.move_prev();
+        assert_eq!(cursor.remove(), Some(1));
+        drop(cursor);
+        assert!(list.is_empty());
+        assert!(list.head.is_none() && list.tail.is_none());
+
+        // elements removed through the cursor and the rest are dropped exactly once
+        let counter = DropCounter::new();
+        let mut list: List<_> = (0..4).map(|i| counter.track(i)).collect();
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        drop(cursor.remove());
+        assert_eq!(counter.dropped(), vec![1]);
+        drop(cursor);
+        drop(list);
+        assert_eq!(counter.dropped(), vec![1, 0, 2, 3]);
+    }
+
+    #[test]
+    fn no_leaks() {
+        let before = live_allocations();
+        let mut list = List::new();
+        for i in 0..100_000 {
+            list.push_back(i);
+        }
+        list.push_front(-1);
+        assert_eq!(list.pop_back(), Some(99_999));
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.insert_after(0);
+        cursor.remove();
+        drop(cursor);
+        let copy = list.clone();
+        // one allocation per node
+        assert_eq!(live_allocations() - before, 2 * 100_000);
+        drop(list);
+        drop(copy);
+        assert_eq!(live_allocations(), before);
+    }
+
+    #[test]
+    fn forgotten_handle() {
+        let before = live_allocations();
+        let mut list: List<i32> = (0..3).collect();
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        // leaks the cursor's Weak to the middle node
+        std::mem::forget(cursor);
+        drop(list);
+        // the Weak only pins that node's allocation, its element is dropped
+        assert_eq!(live_allocations() - before, 1);
+    }
+
+    #[test]
+    fn forgotten_cursor_pop() {
+        let counter = DropCounter::new();
+        let mut list: List<_> = (0..3).map(|id| counter.track(id)).collect();
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        std::mem::forget(cursor);
+        // the leaked Weak can't stop the list from unwrapping the middle node
+        assert_eq!(list.pop_front().map(|elem| elem.id), Some(0));
+        assert_eq!(list.pop_front().map(|elem| elem.id), Some(1));
+        drop(list);
+        assert_eq!(counter.dropped(), vec![0, 1, 2]);
+    }
 }
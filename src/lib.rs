@@ -1,6 +1,24 @@
+// The lists keep the tutorial's explicit `return`s and `into_iter` methods on
+// purpose, they make each step of the ownership story visible.
+#![allow(clippy::needless_return)]
+#![allow(clippy::should_implement_trait)]
+
+pub mod banker;
 pub mod fifth;
 pub mod first;
 pub mod fourth;
+pub mod pairing;
 pub mod second;
+pub mod selforg;
 pub mod sixth;
+pub mod skew;
+pub mod skiplist;
+pub mod sync;
 pub mod third;
+pub mod unrolled;
+pub mod xor;
+
+#[cfg(test)]
+mod conformance;
+#[cfg(test)]
+mod test_helpers;
@@ -0,0 +1,802 @@
+// "A Pairing Heap", a priority queue made of the NonNull nodes of "sixth.rs".
+// To learn about:
+//   1) Heap-ordered multiway trees
+//   2) Handles into a linked structure that can't dangle
+
+// Takeaways:
+//   1. Every node is smaller than its children (a min-heap, use Reverse for a
+//      max-heap). The children of a node are a linked list: `child` points to
+//      the leftmost one, `next` to the right sibling and `prev` to the left
+//      sibling, or to the parent for the leftmost child.
+//   2. `push` and `meld` link two trees, the bigger root becomes the leftmost
+//      child of the smaller one: O(1). `pop` removes the root and has to merge
+//      its children back into one tree, it pairs them up left to right, then
+//      merges the pairs right to left: O(log n) amortized.
+//   3. `decrease_key` cuts the node, with its subtree, out of its parent's list
+//      (thanks to `prev` that's O(1)) and links it with the root.
+//   4. A handle can't just be a pointer, the node may be freed or belong to
+//      another heap. Handles share an Rc'd slot with their node, cleared when
+//      the node is freed. The slot also names the heap it belongs to, a heap
+//      melded into another one forwards to it, so `meld` stays O(1).
+//   5. `Ord` is caller code and may panic in the middle of a merge. Before each
+//      comparison every tree is reachable from somewhere, and a guard puts them
+//      all back into one tree while unwinding. The order may be off afterwards,
+//      but no node leaks or dangles.
+//   6. The Rc slots make the heap and its handles !Send and !Sync, unlike sixth.
+
+use std::cell::{Cell, OnceCell, RefCell};
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+use std::rc::Rc;
+
+pub struct PairingHeap<T> {
+    root: Link<T>,
+    len: usize,
+    owner: Rc<Owner>,
+    _boo: PhantomData<T>,
+}
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+struct Node<T> {
+    elem: T,
+    child: Link<T>,
+    next: Link<T>,
+    prev: Link<T>,
+    // shared with the handles of this node, if any
+    slot: Option<Rc<Slot<T>>>,
+}
+
+// a stable reference to an element, for `get`, `decrease_key` and `remove`
+pub struct Handle<T> {
+    slot: Rc<Slot<T>>,
+}
+
+struct Slot<T> {
+    // None once the element left the heap
+    node: Cell<Link<T>>,
+    owner: RefCell<Rc<Owner>>,
+}
+
+// identifies a heap, set to the surviving heap's owner when melded into it
+#[derive(Default)]
+struct Owner {
+    merged_into: OnceCell<Rc<Owner>>,
+}
+
+// Makes `child` the leftmost child of `parent`. Both must be roots of their
+// own tree: no parent, no siblings.
+unsafe fn attach<T>(parent: NonNull<Node<T>>, child: NonNull<Node<T>>) {
+    let first = (*parent.as_ptr()).child;
+    if let Some(first) = first {
+        (*first.as_ptr()).prev = Some(child);
+    }
+    (*child.as_ptr()).next = first;
+    (*child.as_ptr()).prev = Some(parent);
+    (*parent.as_ptr()).child = Some(child);
+}
+
+// Links two roots, returns the new one. Compares before touching anything,
+// if `Ord` panics both trees are left as they were.
+unsafe fn link<T: Ord>(a: NonNull<Node<T>>, b: NonNull<Node<T>>) -> NonNull<Node<T>> {
+    if (*b.as_ptr()).elem < (*a.as_ptr()).elem {
+        attach(b, a);
+        b
+    } else {
+        attach(a, b);
+        a
+    }
+}
+
+// Cuts the tree rooted at `node` out of its parent's list. A root stays as is.
+unsafe fn cut<T>(node: NonNull<Node<T>>) {
+    let prev = (*node.as_ptr()).prev.take();
+    let next = (*node.as_ptr()).next.take();
+    if let Some(prev) = prev {
+        if (*prev.as_ptr()).child == Some(node) {
+            (*prev.as_ptr()).child = next;
+        } else {
+            (*prev.as_ptr()).next = next;
+        }
+    }
+    if let Some(next) = next {
+        (*next.as_ptr()).prev = prev;
+    }
+}
+
+impl<T> PairingHeap<T> {
+    pub fn new() -> Self {
+        PairingHeap {
+            root: None,
+            len: 0,
+            owner: Rc::default(),
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.root.map(|node| unsafe { &(*node.as_ptr()).elem })
+    }
+
+    // the element of `handle`, None if it left the heap or is from another heap
+    pub fn get(&self, handle: &Handle<T>) -> Option<&T> {
+        self.node_of(handle)
+            .map(|node| unsafe { &(*node.as_ptr()).elem })
+    }
+
+    // the handle's node, if it's still in this heap
+    fn node_of(&self, handle: &Handle<T>) -> Link<T> {
+        let node = handle.slot.node.get()?;
+        let mut owner = Rc::clone(&handle.slot.owner.borrow());
+        while let Some(next) = owner.merged_into.get() {
+            owner = Rc::clone(next);
+        }
+        // remember the end of the chain, next time is a single step
+        *handle.slot.owner.borrow_mut() = Rc::clone(&owner);
+        if Rc::ptr_eq(&owner, &self.owner) {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    // Frees a node and returns its element, clearing its handles.
+    unsafe fn free(node: NonNull<Node<T>>) -> T {
+        let node = Box::from_raw(node.as_ptr());
+        if let Some(slot) = &node.slot {
+            slot.node.set(None);
+        }
+        node.elem
+    }
+
+    // Frees the root, with no regard for the order. Its children take its
+    // place in the list of roots, which only Drop ever makes longer than one.
+    fn pop_any(&mut self) -> Option<T> {
+        self.root.map(|node| unsafe {
+            let mut rest = (*node.as_ptr()).next;
+            if let Some(child) = (*node.as_ptr()).child {
+                let mut last = child;
+                while let Some(next) = (*last.as_ptr()).next {
+                    last = next;
+                }
+                (*last.as_ptr()).next = rest;
+                if let Some(rest) = rest {
+                    (*rest.as_ptr()).prev = Some(last);
+                }
+                (*child.as_ptr()).prev = None;
+                rest = Some(child);
+            }
+            if let Some(rest) = rest {
+                (*rest.as_ptr()).prev = None;
+            }
+            self.root = rest;
+            self.len -= 1;
+            return Self::free(node);
+        })
+    }
+}
+
+impl<T: Ord> PairingHeap<T> {
+    pub fn push(&mut self, elem: T) {
+        self.push_node(elem, None);
+    }
+
+    pub fn push_with_handle(&mut self, elem: T) -> Handle<T> {
+        let slot = Rc::new(Slot {
+            node: Cell::new(None),
+            owner: RefCell::new(Rc::clone(&self.owner)),
+        });
+        let node = self.push_node(elem, Some(Rc::clone(&slot)));
+        slot.node.set(Some(node));
+        Handle { slot }
+    }
+
+    fn push_node(&mut self, elem: T, slot: Option<Rc<Slot<T>>>) -> NonNull<Node<T>> {
+        unsafe {
+            let node = NonNull::new_unchecked(Box::into_raw(Box::new(Node {
+                elem,
+                child: None,
+                next: None,
+                prev: None,
+                slot,
+            })));
+            self.len += 1;
+            self.link_root(node);
+            node
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let root = self.root.take()?;
+        unsafe {
+            let child = (*root.as_ptr()).child.take();
+            self.len -= 1;
+            let elem = Self::free(root);
+            self.root = self.merge_pairs(child);
+            Some(elem)
+        }
+    }
+
+    // Moves every element of `other` into this heap, its handles keep working.
+    pub fn meld(&mut self, mut other: PairingHeap<T>) {
+        let _ = other.owner.merged_into.set(Rc::clone(&self.owner));
+        if let Some(theirs) = other.root.take() {
+            self.len += std::mem::take(&mut other.len);
+            unsafe { self.link_root(theirs) };
+        }
+    }
+
+    // Replaces the element of `handle` with a smaller or equal one and returns
+    // the old one. Gives `elem` back if it is bigger, or the handle isn't in
+    // this heap (anymore).
+    pub fn decrease_key(&mut self, handle: &Handle<T>, elem: T) -> Result<T, T> {
+        let node = match self.node_of(handle) {
+            Some(node) => node,
+            None => return Err(elem),
+        };
+        unsafe {
+            if elem > (*node.as_ptr()).elem {
+                return Err(elem);
+            }
+            let old = std::mem::replace(&mut (*node.as_ptr()).elem, elem);
+            if self.root != Some(node) {
+                cut(node);
+                self.link_root(node);
+            }
+            Ok(old)
+        }
+    }
+
+    // Takes the element of `handle` out of the heap.
+    pub fn remove(&mut self, handle: &Handle<T>) -> Option<T> {
+        let node = self.node_of(handle)?;
+        if self.root == Some(node) {
+            return self.pop();
+        }
+        unsafe {
+            cut(node);
+            let child = (*node.as_ptr()).child.take();
+            self.len -= 1;
+            let elem = Self::free(node);
+            if let Some(sub) = self.merge_pairs(child) {
+                self.link_root(sub);
+            }
+            Some(elem)
+        }
+    }
+
+    // Links `tree`, cut out of everything else, with the heap's root. If `Ord`
+    // panics the guard makes it a child of the root, without comparing.
+    unsafe fn link_root(&mut self, tree: NonNull<Node<T>>) {
+        struct Orphan<'a, T> {
+            heap: &'a mut PairingHeap<T>,
+            tree: NonNull<Node<T>>,
+        }
+        impl<'a, T> Drop for Orphan<'a, T> {
+            fn drop(&mut self) {
+                unsafe { attach(self.heap.root.unwrap(), self.tree) }
+            }
+        }
+
+        let root = match self.root {
+            Some(root) => root,
+            None => {
+                self.root = Some(tree);
+                return;
+            }
+        };
+        let orphan = Orphan { heap: self, tree };
+        let new_root = link(root, tree);
+        std::mem::forget(orphan);
+        self.root = Some(new_root);
+    }
+
+    // Two-pass merge of the sibling list starting at `first`, returns the root.
+    unsafe fn merge_pairs(&mut self, first: Link<T>) -> Link<T> {
+        // Every tree is either in `rest`, the siblings not looked at yet, or
+        // in `pairs`, a list of roots through `next`. If `Ord` panics the
+        // guard attaches them all to the heap's root, without comparing.
+        struct Forest<'a, T> {
+            heap: &'a mut PairingHeap<T>,
+            rest: Link<T>,
+            pairs: Link<T>,
+        }
+        impl<'a, T> Forest<'a, T> {
+            unsafe fn pop_pair(&mut self) -> Link<T> {
+                let node = self.pairs?;
+                self.pairs = (*node.as_ptr()).next.take();
+                Some(node)
+            }
+            unsafe fn push_pair(&mut self, node: NonNull<Node<T>>) {
+                (*node.as_ptr()).next = self.pairs;
+                self.pairs = Some(node);
+            }
+            // Links the top two of `pairs`, the lower one wins ties. Like
+            // `link`, but both stay in `pairs` until the comparison is done.
+            unsafe fn link_pairs(&mut self)
+            where
+                T: Ord,
+            {
+                let top = self.pairs.unwrap();
+                let below = (*top.as_ptr()).next.unwrap();
+                let top_wins = (*top.as_ptr()).elem < (*below.as_ptr()).elem;
+                self.pop_pair();
+                self.pop_pair();
+                let new_root = if top_wins {
+                    attach(top, below);
+                    top
+                } else {
+                    attach(below, top);
+                    below
+                };
+                self.push_pair(new_root);
+            }
+            unsafe fn pop_rest(&mut self) -> Link<T> {
+                let node = self.rest?;
+                self.rest = (*node.as_ptr()).next.take();
+                (*node.as_ptr()).prev = None;
+                if let Some(rest) = self.rest {
+                    (*rest.as_ptr()).prev = None;
+                }
+                Some(node)
+            }
+        }
+        impl<'a, T> Drop for Forest<'a, T> {
+            fn drop(&mut self) {
+                unsafe {
+                    while let Some(node) = self.pop_rest().or_else(|| self.pop_pair()) {
+                        match self.heap.root {
+                            Some(root) => attach(root, node),
+                            None => self.heap.root = Some(node),
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut forest = Forest {
+            heap: self,
+            rest: first,
+            pairs: None,
+        };
+        if let Some(first) = first {
+            (*first.as_ptr()).prev = None;
+        }
+
+        // left to right, link the siblings two by two
+        while let Some(a) = forest.pop_rest() {
+            forest.push_pair(a);
+            if let Some(b) = forest.pop_rest() {
+                forest.push_pair(b);
+                forest.link_pairs();
+            }
+        }
+        // right to left, as `pairs` is reversed, fold the pairs into one tree
+        while let Some(top) = forest.pairs {
+            if (*top.as_ptr()).next.is_none() {
+                break;
+            }
+            forest.link_pairs();
+        }
+        let root = forest.pop_pair();
+        std::mem::forget(forest);
+        root
+    }
+}
+
+impl<T> Drop for PairingHeap<T> {
+    fn drop(&mut self) {
+        struct DropGuard<'a, T>(&'a mut PairingHeap<T>);
+        impl<'a, T> Drop for DropGuard<'a, T> {
+            fn drop(&mut self) {
+                while self.0.pop_any().is_some() {}
+            }
+        }
+
+        while let Some(elem) = self.pop_any() {
+            let guard = DropGuard(self);
+            drop(elem);
+            std::mem::forget(guard);
+        }
+    }
+}
+
+// into iterator, pops the elements in order
+pub struct IntoIter<T>(PairingHeap<T>);
+
+impl<T: Ord> PairingHeap<T> {
+    pub fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+impl<T: Ord> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T: Ord> ExactSizeIterator for IntoIter<T> {}
+impl<T: Ord> std::iter::FusedIterator for IntoIter<T> {}
+
+//////////////////////
+// other interfaces //
+//////////////////////
+
+impl<T> Default for PairingHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Handle {
+            slot: Rc::clone(&self.slot),
+        }
+    }
+}
+
+impl<T: Ord> Extend<T> for PairingHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+impl<T: Ord> FromIterator<T> for PairingHeap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut heap = Self::new();
+        heap.extend(iter);
+        heap
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PairingHeap;
+    use crate::test_helpers::{panics, DropCounter, Tracked};
+    use std::cell::Cell;
+    use std::cmp::{Ordering, Reverse};
+    use std::collections::BinaryHeap;
+    use std::rc::Rc;
+
+    // ordered by key, dropping is tracked by the counter
+    struct Keyed(i32, Tracked);
+
+    impl PartialEq for Keyed {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+    impl Eq for Keyed {}
+    impl PartialOrd for Keyed {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+    impl Ord for Keyed {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.0.cmp(&other.0)
+        }
+    }
+
+    // an Ord that panics once the fuse, the comparisons left, is burnt
+    struct Bomb(i32, Rc<Cell<usize>>);
+
+    const DISARMED: usize = usize::MAX;
+
+    impl PartialEq for Bomb {
+        fn eq(&self, other: &Self) -> bool {
+            self.cmp(other) == Ordering::Equal
+        }
+    }
+    impl Eq for Bomb {}
+    impl PartialOrd for Bomb {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+    impl Ord for Bomb {
+        fn cmp(&self, other: &Self) -> Ordering {
+            match self.1.get() {
+                0 => panic!("Bomb went off"),
+                DISARMED => {}
+                left => self.1.set(left - 1),
+            }
+            self.0.cmp(&other.0)
+        }
+    }
+
+    #[test]
+    fn basics() {
+        let mut heap = PairingHeap::new();
+        assert_eq!(heap.peek(), None);
+        assert_eq!(heap.pop(), None);
+
+        heap.extend(vec![5, 1, 8, 3, 9, 2]);
+        assert_eq!(heap.len(), 6);
+        assert_eq!(heap.peek(), Some(&1));
+        assert_eq!(heap.pop(), Some(1));
+        assert_eq!(heap.pop(), Some(2));
+        heap.push(0);
+        assert_eq!(heap.peek(), Some(&0));
+        assert_eq!(heap.into_iter().collect::<Vec<_>>(), vec![0, 3, 5, 8, 9]);
+
+        let heap: PairingHeap<_> = vec![3, 1, 2].into_iter().map(Reverse).collect();
+        assert_eq!(
+            heap.into_iter().map(|r| r.0).collect::<Vec<_>>(),
+            vec![3, 2, 1]
+        );
+    }
+
+    #[test]
+    fn handles() {
+        let mut heap = PairingHeap::new();
+        let handles: Vec<_> = (0..10).map(|i| heap.push_with_handle(i * 10)).collect();
+        assert_eq!(heap.get(&handles[3]), Some(&30));
+
+        // bigger isn't a decrease, the heap is unchanged
+        assert_eq!(heap.decrease_key(&handles[3], 31), Err(31));
+        assert_eq!(heap.decrease_key(&handles[3], 30), Ok(30));
+        assert_eq!(heap.decrease_key(&handles[7], -1), Ok(70));
+        assert_eq!(heap.peek(), Some(&-1));
+        assert_eq!(heap.get(&handles[7]), Some(&-1));
+
+        assert_eq!(heap.remove(&handles[5]), Some(50));
+        assert_eq!(heap.remove(&handles[5]), None);
+        assert_eq!(heap.get(&handles[5]), None);
+        assert_eq!(heap.pop(), Some(-1));
+        // popped, the handle is stale now
+        assert_eq!(heap.decrease_key(&handles[7], -2), Err(-2));
+        assert_eq!(heap.remove(&handles[0]), Some(0));
+        assert_eq!(heap.len(), 7);
+        assert_eq!(
+            heap.into_iter().collect::<Vec<_>>(),
+            vec![10, 20, 30, 40, 60, 80, 90]
+        );
+        // the heap is gone, so are all its elements
+        assert!(handles
+            .iter()
+            .all(|handle| handle.slot.node.get().is_none()));
+    }
+
+    #[test]
+    fn meld() {
+        let mut a = PairingHeap::new();
+        let mut b = PairingHeap::new();
+        let mut c = PairingHeap::new();
+        let in_a = a.push_with_handle(5);
+        let in_b = b.push_with_handle(7);
+        let in_c = c.push_with_handle(3);
+        b.push(1);
+
+        // a handle only works with its own heap
+        assert_eq!(a.get(&in_b), None);
+        assert_eq!(a.decrease_key(&in_b, 0), Err(0));
+        assert_eq!(a.remove(&in_c), None);
+
+        b.meld(c);
+        a.meld(b);
+        assert_eq!(a.len(), 4);
+        // handles of melded heaps follow their elements
+        assert_eq!(a.get(&in_a), Some(&5));
+        assert_eq!(a.decrease_key(&in_b, 0), Ok(7));
+        assert_eq!(a.remove(&in_c), Some(3));
+        assert_eq!(a.into_iter().collect::<Vec<_>>(), vec![0, 1, 5]);
+
+        let mut empty = PairingHeap::new();
+        empty.meld(PairingHeap::new());
+        empty.meld(vec![2, 1].into_iter().collect());
+        assert_eq!(empty.pop(), Some(1));
+    }
+
+    #[test]
+    fn against_binary_heap() {
+        let mut heap = PairingHeap::new();
+        let mut model = BinaryHeap::new();
+        let mut handles = Vec::new();
+        let mut seed = 0x9e37_79b9_7f4a_7c15u64;
+        for i in 0..5000i64 {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            let value = (seed % 1000) as i64;
+            match seed % 6 {
+                0 | 1 => {
+                    handles.push((heap.push_with_handle((value, i)), (value, i)));
+                    model.push(Reverse((value, i)));
+                }
+                2 => {
+                    assert_eq!(heap.pop(), model.pop().map(|r| r.0));
+                }
+                3 | 4 if !handles.is_empty() => {
+                    // decrease a random live element, the model rebuilds
+                    let pick = (seed >> 32) as usize % handles.len();
+                    let (handle, old) = &mut handles[pick];
+                    if heap.get(handle).is_some() {
+                        let new = (old.0 - value, old.1);
+                        assert_eq!(heap.decrease_key(handle, new), Ok(*old));
+                        model = model
+                            .into_iter()
+                            .map(|r| if r.0 == *old { Reverse(new) } else { r })
+                            .collect();
+                        *old = new;
+                    }
+                }
+                _ if !handles.is_empty() => {
+                    let pick = (seed >> 32) as usize % handles.len();
+                    let (handle, old) = handles.swap_remove(pick);
+                    if let Some(elem) = heap.remove(&handle) {
+                        assert_eq!(elem, old);
+                        model.retain(|r| r.0 != old);
+                    }
+                }
+                _ => {}
+            }
+            assert_eq!(heap.len(), model.len());
+            assert_eq!(heap.peek(), model.peek().map(|r| &r.0));
+        }
+        assert_eq!(
+            heap.into_iter().collect::<Vec<_>>(),
+            model
+                .into_sorted_vec()
+                .into_iter()
+                .rev()
+                .map(|r| r.0)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn drop_order() {
+        let counter = DropCounter::new();
+        let mut heap = PairingHeap::new();
+        for id in 0..100 {
+            heap.push(Keyed(id as i32 % 7, counter.track(id)));
+        }
+        for _ in 0..10 {
+            let popped = heap.pop().unwrap();
+            assert_eq!(popped.1.id % 7, 0);
+        }
+        assert_eq!(counter.count(), 10);
+        drop(heap);
+        // each one exactly once
+        let mut dropped = counter.dropped();
+        dropped.sort();
+        assert_eq!(dropped, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn drop_panic() {
+        let counter = DropCounter::new();
+        let mut heap = PairingHeap::new();
+        for id in 0..10 {
+            heap.push(Keyed(id as i32, counter.track(id)));
+        }
+        heap.push(Keyed(5, counter.panic_on_drop(10)));
+
+        assert!(panics(move || drop(heap)));
+        let mut dropped = counter.dropped();
+        dropped.sort();
+        assert_eq!(dropped, (0..11).collect::<Vec<_>>());
+    }
+
+    // every element still in the heap, as many as `len` says
+    fn keys(heap: PairingHeap<Bomb>) -> Vec<i32> {
+        let len = heap.len();
+        let mut keys: Vec<i32> = heap.into_iter().map(|bomb| bomb.0).collect();
+        assert_eq!(keys.len(), len);
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn ord_panic() {
+        let fuse = Rc::new(Cell::new(DISARMED));
+        let mut heap = PairingHeap::new();
+        let handles: Vec<_> = (0..20)
+            .map(|i| heap.push_with_handle(Bomb(i, Rc::clone(&fuse))))
+            .collect();
+
+        fuse.set(0);
+        assert!(panics(|| {
+            heap.pop();
+        }));
+        fuse.set(DISARMED);
+
+        // the heap may be out of order, but every element is still in it
+        assert_eq!(heap.len(), 19);
+        assert!(handles[1..].iter().all(|handle| heap.get(handle).is_some()));
+        let old = heap.decrease_key(&handles[10], Bomb(-1, Rc::clone(&fuse)));
+        assert_eq!(old.map(|bomb| bomb.0).map_err(|bomb| bomb.0), Ok(10));
+        let keys = keys(heap);
+        assert_eq!(keys[0], -1);
+        assert_eq!(
+            keys[1..],
+            (1..20).filter(|&i| i != 10).collect::<Vec<_>>()[..]
+        );
+    }
+
+    #[test]
+    fn ord_panic_when_linking() {
+        let fuse = Rc::new(Cell::new(DISARMED));
+        let bomb = |i| Bomb(i, Rc::clone(&fuse));
+        let heap_of = |range: std::ops::Range<i32>| {
+            let mut heap = PairingHeap::new();
+            let handles: Vec<_> = range.map(|i| heap.push_with_handle(bomb(i))).collect();
+            (heap, handles)
+        };
+
+        // decrease_key compares with the old element, then with the root
+        let (mut heap, handles) = heap_of(0..10);
+        fuse.set(1);
+        assert!(panics(|| {
+            let _ = heap.decrease_key(&handles[7], bomb(-1));
+        }));
+        fuse.set(DISARMED);
+        assert_eq!(heap.get(&handles[7]).map(|bomb| bomb.0), Some(-1));
+        assert_eq!(keys(heap), vec![-1, 0, 1, 2, 3, 4, 5, 6, 8, 9]);
+
+        // pushed in decreasing order the heap is a path, remove links the
+        // rest of the path with the root
+        let mut heap = PairingHeap::new();
+        let handles: Vec<_> = (0..10)
+            .rev()
+            .map(|i| heap.push_with_handle(bomb(i)))
+            .collect();
+        let handles: Vec<_> = handles.into_iter().rev().collect();
+        heap.pop();
+        fuse.set(0);
+        assert!(panics(|| {
+            heap.remove(&handles[5]);
+        }));
+        fuse.set(DISARMED);
+        assert_eq!(keys(heap), vec![1, 2, 3, 4, 6, 7, 8, 9]);
+
+        let (mut heap, _) = heap_of(0..10);
+        fuse.set(0);
+        assert!(panics(|| heap.push(bomb(-1))));
+        fuse.set(DISARMED);
+        assert_eq!(keys(heap), (-1..10).collect::<Vec<_>>());
+
+        let (mut heap, _) = heap_of(0..10);
+        let (other, handles) = heap_of(10..20);
+        fuse.set(0);
+        assert!(panics(|| heap.meld(other)));
+        fuse.set(DISARMED);
+        assert_eq!(heap.get(&handles[3]).map(|bomb| bomb.0), Some(13));
+        assert_eq!(keys(heap), (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn long_chains() {
+        // decreasing pushes make a single path 100k nodes deep, increasing
+        // pushes a root with 100k children
+        let mut heap: PairingHeap<i32> = (0..100_000).rev().collect();
+        assert_eq!(heap.pop(), Some(0));
+        drop(heap);
+
+        let mut heap: PairingHeap<i32> = (0..100_000).collect();
+        assert_eq!(heap.pop(), Some(0));
+        assert_eq!(heap.pop(), Some(1));
+        drop(heap);
+
+        let heap: PairingHeap<i32> = (0..100_000).rev().collect();
+        assert!(heap.into_iter().eq(0..100_000));
+    }
+}
@@ -18,9 +18,14 @@
 //   8) use as_deref() to convert Option<T> to Option<&T>
 //   9) iter_mut will take the ownership of element
 //   10) Option<T>::map consumes the option itself.
+//   11) Keep a `len` counter next to the head, so len() is O(1) and iterators
+//       can implement ExactSizeIterator (which lets `collect` pre-allocate)
+
+use std::iter::FusedIterator;
 
 pub struct List<T> {
     head: Link<T>,
+    len: usize,
 }
 
 type Link<T> = Option<Box<Node<T>>>;
@@ -33,7 +38,7 @@ struct Node<T> {
 
 impl<T> List<T> {
     pub fn new() -> Self {
-        return List { head: None };
+        return List { head: None, len: 0 };
     }
 
     // a stack
@@ -44,6 +49,7 @@ impl<T> List<T> {
         });
 
         self.head = Some(node);
+        self.len += 1;
     }
 
     pub fn pop(&mut self) -> Option<T> {
@@ -53,6 +59,7 @@ impl<T> List<T> {
         // if Some(x), the function takes x and returns y, and wrap result with Some
         return self.head.take().map(|node| {
             self.head = node.next;
+            self.len -= 1;
             return node.value;
         });
     }
@@ -68,16 +75,106 @@ impl<T> List<T> {
             return &mut node.value;
         });
     }
+
+    pub fn len(&self) -> usize {
+        return self.len;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.len == 0;
+    }
+}
+
+// bulk operations, they only relink nodes and never move or clone a T
+impl<T> List<T> {
+    // the top becomes the bottom
+    pub fn reverse(&mut self) {
+        let mut reversed: Link<T> = None;
+        let mut cur = self.head.take();
+        while let Some(mut node) = cur {
+            // point the node back to the part we already reversed
+            cur = node.next.take();
+            node.next = reversed;
+            reversed = Some(node);
+        }
+        self.head = reversed;
+    }
+
+    // Puts all of `other` on top of this stack, leaving `other` empty.
+    // `other`'s top becomes the new top.
+    pub fn append(&mut self, other: &mut Self) {
+        if other.head.is_none() {
+            return;
+        }
+        // hang our nodes below other's bottom node
+        *other.bottom_link() = self.head.take();
+        self.head = other.head.take();
+        self.len += other.len;
+        other.len = 0;
+    }
+
+    // Keeps the top `at` items and returns the ones below them as a new list.
+    // Returns an empty list if there are no more than `at` items.
+    pub fn split_off(&mut self, at: usize) -> List<T> {
+        let at = at.min(self.len);
+        let bottom = List {
+            head: self.link_below(at).take(),
+            len: self.len - at,
+        };
+        self.len = at;
+        return bottom;
+    }
+
+    // Keeps the top `len` items and drops the rest.
+    pub fn truncate(&mut self, len: usize) {
+        // the split off list drops its nodes iteratively
+        drop(self.split_off(len));
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    // the link below the top `n` nodes, or the empty bottom link
+    // if there are not that many
+    fn link_below(&mut self, n: usize) -> &mut Link<T> {
+        let mut link = &mut self.head;
+        for _ in 0..n {
+            if link.is_none() {
+                break;
+            }
+            link = &mut link.as_mut().unwrap().next;
+        }
+        return link;
+    }
+
+    // the empty link after the last node
+    fn bottom_link(&mut self) -> &mut Link<T> {
+        let mut link = &mut self.head;
+        while let Some(node) = link {
+            link = &mut node.next;
+        }
+        return link;
+    }
 }
 
 // we have to implement Drop in an iterative manner
 // to avoid recursive call blowing the stack
 impl<T> Drop for List<T> {
     fn drop(&mut self) {
-        let mut cur_link = self.head.take();
-        // `while let` == "do this thing until this pattern doesn't match"
-        while let Some(mut node) = cur_link {
-            cur_link = node.next.take();
+        // if dropping an element panics, keep popping the rest while unwinding,
+        // a chain left in a local would be dropped recursively and blow the stack
+        struct DropGuard<'a, T>(&'a mut List<T>);
+        impl<'a, T> Drop for DropGuard<'a, T> {
+            fn drop(&mut self) {
+                while self.0.pop().is_some() {}
+            }
+        }
+
+        while let Some(elem) = self.pop() {
+            let guard = DropGuard(self);
+            drop(elem);
+            std::mem::forget(guard);
         }
     }
 }
@@ -87,9 +184,11 @@ pub struct IntoIter<T>(List<T>);
 // const ref
 pub struct Iter<'a, T> {
     next: Option<&'a Node<T>>,
+    len: usize,
 }
 pub struct IterMut<'a, T> {
     next: Option<&'a mut Node<T>>,
+    len: usize,
 }
 
 // iterators
@@ -97,16 +196,18 @@ impl<T> List<T> {
     pub fn into_iter(self) -> IntoIter<T> {
         return IntoIter(self);
     }
-    pub fn iter(&self) -> Iter<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
         // we want an Option<&Node<T>>, but we have an Option<Node<T>>
         // as_deref() converts from Option<T> to Option<&T>
         return Iter {
             next: self.head.as_deref(),
+            len: self.len,
         };
     }
-    pub fn iter_mut(&mut self) -> IterMut<T> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         return IterMut {
             next: self.head.as_deref_mut(),
+            len: self.len,
         };
     }
 }
@@ -121,6 +222,10 @@ impl<T> Iterator for IntoIter<T> {
     fn next(&mut self) -> Option<Self::Item> {
         return self.0.pop();
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return (self.0.len, Some(self.0.len));
+    }
 }
 
 impl<'a, T> Iterator for Iter<'a, T> {
@@ -132,9 +237,14 @@ impl<'a, T> Iterator for Iter<'a, T> {
             // node.next is an Option<Node<T>>, but we want to return Option<&Node<T>>
             // still, we need as_deref
             self.next = node.next.as_deref();
+            self.len -= 1;
             return &node.value;
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return (self.len, Some(self.len));
+    }
 }
 
 impl<'a, T> Iterator for IterMut<'a, T> {
@@ -146,9 +256,107 @@ impl<'a, T> Iterator for IterMut<'a, T> {
         // not the node itself
         self.next.take().map(|node| {
             self.next = node.next.as_deref_mut();
+            self.len -= 1;
             return &mut node.value;
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return (self.len, Some(self.len));
+    }
+}
+
+// size_hint is exact, and once exhausted they keep returning None
+impl<T> ExactSizeIterator for IntoIter<T> {}
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+impl<'a, T> FusedIterator for IterMut<'a, T> {}
+
+//////////////////////
+// other interfaces //
+//////////////////////
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl<T: std::hash::Hash> std::hash::Hash for List<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for item in self.iter() {
+            item.hash(state);
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for List<T> {
+    // from the top to the bottom
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return f.debug_list().entries(self.iter()).finish();
+    }
+}
+
+impl<T: Clone> Clone for List<T> {
+    fn clone(&self) -> Self {
+        return self.iter().cloned().collect();
+    }
+}
+
+// a stack: every item is pushed, so the last one ends up on top
+impl<T> Extend<T> for List<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a> Extend<&'a T> for List<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+// unlike extend, keeps the order of the iterator: the first item ends up on
+// top, so `list.iter().cloned().collect()` gives the same stack back
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = List::new();
+        // append at the bottom, through a &mut to the last (empty) link
+        let mut tail = &mut list.head;
+        for item in iter {
+            let node = tail.insert(Box::new(Node {
+                value: item,
+                next: None,
+            }));
+            tail = &mut node.next;
+            list.len += 1;
+        }
+        return list;
+    }
+}
+
+impl<T: PartialEq> PartialEq for List<T> {
+    fn eq(&self, other: &Self) -> bool {
+        return self.len() == other.len() && self.iter().eq(other.iter());
+    }
+}
+impl<T: Eq> Eq for List<T> {}
+
+impl<T: PartialOrd> PartialOrd for List<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        return self.iter().partial_cmp(other.iter());
+    }
+}
+impl<T: Ord> Ord for List<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        return self.iter().cmp(other.iter());
+    }
 }
 
 // unit tests
@@ -157,6 +365,7 @@ impl<'a, T> Iterator for IterMut<'a, T> {
 #[cfg(test)]
 mod first_list_tests {
     use super::List;
+    use crate::test_helpers::{panics, DropCounter};
 
     #[test]
     fn push_and_pop() {
@@ -190,6 +399,7 @@ mod first_list_tests {
     }
 
     #[test]
+    #[allow(clippy::option_map_unit_fn)]
     fn peek() {
         let mut list = List::new();
         assert_eq!(list.peek(), None);
@@ -269,4 +479,219 @@ mod first_list_tests {
         let mut iter1 = list.iter_mut();
         assert_eq!(iter1.next(), Some(&mut 6));
     }
+
+    #[test]
+    fn drop_order() {
+        let counter = DropCounter::new();
+        let mut list = List::new();
+        for id in 0..5 {
+            list.push(counter.track(id));
+        }
+        assert_eq!(counter.count(), 0);
+
+        drop(list);
+        assert_eq!(counter.dropped(), vec![4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn drop_panic() {
+        let counter = DropCounter::new();
+        let mut list = List::new();
+        list.push(counter.track(0));
+        list.push(counter.track(1));
+        list.push(counter.panic_on_drop(2));
+        list.push(counter.track(3));
+        list.push(counter.track(4));
+
+        // the nodes after the panicking one are still dropped, exactly once
+        assert!(panics(move || drop(list)));
+        assert_eq!(counter.dropped(), vec![4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn drop_panic_long() {
+        // the panic comes first, the million nodes behind it must still be
+        // dropped one by one and not recursively
+        let counter = DropCounter::new();
+        let mut list = List::new();
+        for id in 0..1_000_000 {
+            list.push(counter.track(id));
+        }
+        list.push(counter.panic_on_drop(1_000_000));
+
+        assert!(panics(move || drop(list)));
+        assert_eq!(counter.count(), 1_000_001);
+    }
+
+    fn list_of(values: &[i32]) -> List<i32> {
+        // the first value ends up on top
+        let mut list = List::new();
+        for value in values.iter().rev() {
+            list.push(*value);
+        }
+        return list;
+    }
+
+    fn values(list: &List<i32>) -> Vec<i32> {
+        return list.iter().copied().collect();
+    }
+
+    #[test]
+    fn reverse() {
+        let mut list = list_of(&[1, 2, 3, 4]);
+        // nodes are relinked, the values stay where they are
+        let top = list.peek().unwrap() as *const i32;
+        list.reverse();
+        assert_eq!(values(&list), vec![4, 3, 2, 1]);
+        assert!(std::ptr::eq(list.iter().last().unwrap(), top));
+
+        let mut empty = List::<i32>::new();
+        empty.reverse();
+        assert_eq!(empty.peek(), None);
+    }
+
+    #[test]
+    fn append() {
+        let mut list = list_of(&[3, 4]);
+        let mut other = list_of(&[1, 2]);
+        list.append(&mut other);
+        assert_eq!(values(&list), vec![1, 2, 3, 4]);
+        assert_eq!(other.peek(), None);
+
+        // appending empty lists in both directions
+        list.append(&mut other);
+        assert_eq!(values(&list), vec![1, 2, 3, 4]);
+        other.append(&mut list);
+        assert_eq!(values(&other), vec![1, 2, 3, 4]);
+        assert_eq!(list.peek(), None);
+    }
+
+    #[test]
+    fn split_off() {
+        let mut list = list_of(&[1, 2, 3, 4, 5]);
+        let bottom = list.split_off(2);
+        assert_eq!(values(&list), vec![1, 2]);
+        assert_eq!(values(&bottom), vec![3, 4, 5]);
+
+        let bottom = list.split_off(2);
+        assert_eq!(values(&list), vec![1, 2]);
+        assert_eq!(bottom.peek(), None);
+
+        let all = list.split_off(0);
+        assert_eq!(list.peek(), None);
+        assert_eq!(values(&all), vec![1, 2]);
+    }
+
+    #[test]
+    fn truncate_and_clear() {
+        let counter = DropCounter::new();
+        let mut list = List::new();
+        for id in 0..5 {
+            list.push(counter.track(id));
+        }
+
+        list.truncate(10);
+        assert_eq!(counter.count(), 0);
+        list.truncate(2);
+        assert_eq!(counter.dropped(), vec![2, 1, 0]);
+        assert_eq!(list.iter().map(|x| x.id).collect::<Vec<_>>(), vec![4, 3]);
+
+        // none of these drop anything
+        let mut other = List::new();
+        other.push(counter.track(5));
+        list.append(&mut other);
+        list.reverse();
+        assert_eq!(counter.count(), 3);
+        assert_eq!(list.iter().map(|x| x.id).collect::<Vec<_>>(), vec![3, 4, 5]);
+
+        list.clear();
+        assert_eq!(list.peek().map(|x| x.id), None);
+        assert_eq!(counter.dropped(), vec![2, 1, 0, 3, 4, 5]);
+    }
+
+    #[test]
+    fn len() {
+        let mut list = List::new();
+        assert!(list.is_empty());
+        for i in 0..5 {
+            list.push(i);
+        }
+        assert_eq!(list.len(), 5);
+        list.pop();
+        assert_eq!(list.len(), 4);
+
+        let mut other = list_of(&[7, 8]);
+        list.append(&mut other);
+        assert_eq!((list.len(), other.len()), (6, 0));
+        let bottom = list.split_off(4);
+        assert_eq!((list.len(), bottom.len()), (4, 2));
+        let empty = list.split_off(10);
+        assert_eq!((list.len(), empty.len()), (4, 0));
+        list.truncate(1);
+        assert_eq!(list.len(), 1);
+        list.clear();
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn exact_size() {
+        let mut list = list_of(&[1, 2, 3]);
+
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 3);
+        iter.next();
+        assert_eq!(iter.size_hint(), (2, Some(2)));
+        iter.next();
+        iter.next();
+        assert_eq!(iter.len(), 0);
+        // fused: keeps returning None
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+
+        let mut iter = list.iter_mut();
+        iter.next();
+        assert_eq!(iter.len(), 2);
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.len(), 3);
+        iter.next();
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn traits() {
+        let empty: List<i32> = Default::default();
+        assert!(empty.is_empty());
+
+        // collect keeps the order, the first item is the top
+        let a: List<i32> = (1..=3).collect();
+        assert_eq!(a.peek(), Some(&1));
+        assert_eq!(format!("{:?}", a), "[1, 2, 3]");
+
+        // extend pushes, the last item is the top
+        let mut b = List::new();
+        b.extend(vec![3, 2]);
+        b.extend(&[1]);
+        assert_eq!(a, b);
+        assert_eq!(a.clone(), a);
+
+        b.pop();
+        assert_ne!(a, b);
+        assert!(a < b);
+        assert!(b > a);
+        assert!(empty < a);
+        assert_eq!(a.cmp(&a), std::cmp::Ordering::Equal);
+        assert_eq!(
+            list_of(&[1, 2]).partial_cmp(&b),
+            Some(std::cmp::Ordering::Less)
+        );
+
+        let mut map = std::collections::HashMap::new();
+        map.insert(a.clone(), "a");
+        map.insert(b.clone(), "b");
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&a), Some(&"a"));
+        assert_eq!(map.get(&b), Some(&"b"));
+    }
 }
@@ -0,0 +1,315 @@
+// "A Self-Organizing List", a search list on top of "sixth.rs" that moves what
+// it finds towards the front. To learn about:
+//   1) Adapting a data structure to its access pattern
+//   2) Relinking nodes instead of moving elements
+
+// Takeaways:
+//   1. A linear search costs the position of what it finds. When a few keys get
+//      most of the lookups, keeping those near the front makes the average
+//      search much shorter than n / 2, without any index or hashing.
+//   2. MoveToFront puts every hit at the front. It adapts fast, and it is never
+//      worse than twice the best static order (Sleator and Tarjan), but one
+//      lookup of a cold key is enough to push all hot ones back by one.
+//   3. Transpose swaps a hit with its predecessor. It adapts slowly, and is
+//      more stable once the order is right.
+//   4. FrequencyCount keeps a hit counter per element, and the list sorted by
+//      it. It is close to the best static order, but the counters never
+//      forget, so it is slow to follow a change in the pattern.
+//   5. Every policy only relinks the found node: no element is moved and no
+//      node is allocated or freed. MoveToFront and Transpose do it in O(1)
+//      once the node is found, FrequencyCount first walks back over the
+//      predecessors it overtakes, O(distance). That needs crate-private node
+//      handles from "sixth.rs", since its nodes are private.
+
+use crate::sixth::{self, LinkedList};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Policy {
+    MoveToFront,
+    Transpose,
+    FrequencyCount,
+}
+
+// lookups so far, `probes` counts every element looked at by `find`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub hits: usize,
+    pub misses: usize,
+    pub probes: usize,
+}
+
+pub struct List<T> {
+    list: LinkedList<Entry<T>>,
+    policy: Policy,
+    stats: Stats,
+}
+
+struct Entry<T> {
+    elem: T,
+    // only kept up to date under FrequencyCount
+    hits: usize,
+}
+
+impl<T> List<T> {
+    pub fn new(policy: Policy) -> Self {
+        List {
+            list: LinkedList::new(),
+            policy,
+            stats: Stats::default(),
+        }
+    }
+
+    pub fn policy(&self) -> Policy {
+        self.policy
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = Stats::default();
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    // new elements start at the back, with no hits
+    pub fn insert(&mut self, elem: T) {
+        self.list.push_back(Entry { elem, hits: 0 });
+    }
+
+    // the element at the back, the coldest one, for evicting
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.list.pop_back().map(|entry| entry.elem)
+    }
+
+    // Looks for the first element matching `pred` and reorganizes the list
+    // around it according to the policy.
+    pub fn find<P: FnMut(&T) -> bool>(&mut self, mut pred: P) -> Option<&mut T> {
+        let (found, probes) = self.list.find_node(|entry| pred(&entry.elem));
+        self.stats.probes += probes;
+        let node = match found {
+            Some(node) => node,
+            None => {
+                self.stats.misses += 1;
+                return None;
+            }
+        };
+        self.stats.hits += 1;
+
+        // the node came from this list just now
+        unsafe {
+            match self.policy {
+                Policy::MoveToFront => {
+                    let front = self.list.front_node().unwrap();
+                    self.list.move_before(node, front);
+                }
+                Policy::Transpose => {
+                    if let Some(prev) = self.list.prev_node(node) {
+                        self.list.move_before(node, prev);
+                    }
+                }
+                Policy::FrequencyCount => {
+                    let hits = {
+                        let entry = self.list.node_elem_mut(node);
+                        entry.hits += 1;
+                        entry.hits
+                    };
+                    // in front of every element with fewer hits
+                    let mut target = node;
+                    while let Some(prev) = self.list.prev_node(target) {
+                        if self.list.node_elem(prev).hits >= hits {
+                            break;
+                        }
+                        target = prev;
+                    }
+                    self.list.move_before(node, target);
+                }
+            }
+            Some(&mut self.list.node_elem_mut(node).elem)
+        }
+    }
+}
+
+// front to back, the order `find` searches in
+pub struct Iter<'a, T>(sixth::Iter<'a, Entry<T>>);
+
+impl<T> List<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.list.iter())
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|entry| &entry.elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+//////////////////////
+// other interfaces //
+//////////////////////
+
+impl<T: std::fmt::Debug> std::fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Extend<T> for List<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.insert(elem);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{List, Policy, Stats};
+    use crate::test_helpers::total_allocations;
+
+    fn list(policy: Policy, len: i32) -> List<i32> {
+        let mut list = List::new(policy);
+        list.extend(0..len);
+        list
+    }
+
+    fn values(list: &List<i32>) -> Vec<i32> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn move_to_front() {
+        let mut list = list(Policy::MoveToFront, 5);
+        assert_eq!(list.find(|&x| x == 3), Some(&mut 3));
+        assert_eq!(values(&list), vec![3, 0, 1, 2, 4]);
+        assert_eq!(list.find(|&x| x == 4), Some(&mut 4));
+        assert_eq!(values(&list), vec![4, 3, 0, 1, 2]);
+        assert_eq!(list.find(|&x| x == 4), Some(&mut 4));
+        assert_eq!(values(&list), vec![4, 3, 0, 1, 2]);
+        assert_eq!(list.find(|&x| x == 9), None);
+        assert_eq!(values(&list), vec![4, 3, 0, 1, 2]);
+        assert_eq!(
+            list.stats(),
+            Stats {
+                hits: 3,
+                misses: 1,
+                probes: 4 + 5 + 1 + 5,
+            }
+        );
+        assert_eq!(format!("{:?}", list), "[4, 3, 0, 1, 2]");
+        // the back is the least recently found
+        assert_eq!(list.pop_back(), Some(2));
+    }
+
+    #[test]
+    fn transpose() {
+        let mut list = list(Policy::Transpose, 5);
+        list.find(|&x| x == 3);
+        assert_eq!(values(&list), vec![0, 1, 3, 2, 4]);
+        list.find(|&x| x == 3);
+        list.find(|&x| x == 3);
+        assert_eq!(values(&list), vec![3, 0, 1, 2, 4]);
+        list.find(|&x| x == 3);
+        assert_eq!(values(&list), vec![3, 0, 1, 2, 4]);
+        list.find(|&x| x == 4);
+        assert_eq!(values(&list), vec![3, 0, 1, 4, 2]);
+        assert_eq!(list.pop_back(), Some(2));
+    }
+
+    #[test]
+    fn frequency_count() {
+        let mut list = list(Policy::FrequencyCount, 5);
+        list.find(|&x| x == 2);
+        assert_eq!(values(&list), vec![2, 0, 1, 3, 4]);
+        list.find(|&x| x == 4);
+        // ties keep their order
+        assert_eq!(values(&list), vec![2, 4, 0, 1, 3]);
+        list.find(|&x| x == 4);
+        assert_eq!(values(&list), vec![4, 2, 0, 1, 3]);
+        list.find(|&x| x == 3);
+        list.find(|&x| x == 3);
+        assert_eq!(values(&list), vec![4, 3, 2, 0, 1]);
+        list.find(|&x| x == 3);
+        assert_eq!(values(&list), vec![3, 4, 2, 0, 1]);
+        *list.find(|&x| x == 0).unwrap() = 10;
+        assert_eq!(values(&list), vec![3, 4, 2, 10, 1]);
+    }
+
+    #[test]
+    fn edges() {
+        for policy in [
+            Policy::MoveToFront,
+            Policy::Transpose,
+            Policy::FrequencyCount,
+        ] {
+            let mut list = List::new(policy);
+            assert_eq!(list.policy(), policy);
+            assert_eq!(list.find(|_: &i32| true), None);
+            list.insert(1);
+            assert_eq!(list.find(|&x| x == 1), Some(&mut 1));
+            list.insert(2);
+            assert_eq!(list.find(|&x| x == 2), Some(&mut 2));
+            assert_eq!(list.len(), 2);
+            assert_eq!(list.pop_back().unwrap() + list.pop_back().unwrap(), 3);
+            assert!(list.is_empty());
+            assert_eq!(list.stats().hits, 2);
+            list.reset_stats();
+            assert_eq!(list.stats(), Stats::default());
+        }
+    }
+
+    #[test]
+    fn skewed_lookups() {
+        // key k is looked up about twice as often as key k + 1, the cold
+        // keys were inserted first
+        let mut lookups = Vec::new();
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        for _ in 0..5000 {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            lookups.push((seed | 1 << 63).trailing_zeros() as i32);
+        }
+        let unorganized = {
+            let list: Vec<i32> = (0..64).rev().collect();
+            lookups
+                .iter()
+                .map(|&key| list.iter().position(|&x| x == key).unwrap() + 1)
+                .sum::<usize>()
+        };
+
+        for policy in [
+            Policy::MoveToFront,
+            Policy::Transpose,
+            Policy::FrequencyCount,
+        ] {
+            let mut list = List::new(policy);
+            list.extend((0..64).rev());
+            let before = total_allocations();
+            for &key in &lookups {
+                assert_eq!(list.find(|&x| x == key), Some(&mut { key }));
+            }
+            // nothing was allocated, so nothing was reallocated either
+            assert_eq!(total_allocations(), before);
+            assert_eq!(list.len(), 64);
+            let stats = list.stats();
+            assert_eq!((stats.hits, stats.misses), (5000, 0));
+            assert!(stats.probes * 4 < unorganized, "{:?}", policy);
+        }
+    }
+}
@@ -37,7 +37,7 @@ impl<T> LinkedList<T> {
             let new_head = std::ptr::NonNull::new_unchecked(Box::into_raw(Box::new(Node {
                 prev: None,
                 next: None,
-                elem: elem,
+                elem,
             })));
 
             match self.front {
@@ -67,7 +67,7 @@ impl<T> LinkedList<T> {
             let new_tail = std::ptr::NonNull::new_unchecked(Box::into_raw(Box::new(Node {
                 prev: None,
                 next: None,
-                elem: elem,
+                elem,
             })));
 
             if let Some(old_tail) = self.back {
@@ -135,6 +135,10 @@ impl<T> LinkedList<T> {
         self.len
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
     pub fn front(&self) -> Option<&T> {
         self.front.map(|node| unsafe { &node.as_ref().elem })
     }
@@ -155,7 +159,21 @@ impl<T> LinkedList<T> {
 
 impl<T> Drop for LinkedList<T> {
     fn drop(&mut self) {
-        while let Some(_) = self.pop_front() {}
+        // Panic safety: if dropping an element panics, the guard keeps
+        // popping the remaining elements while unwinding. Without it the
+        // rest of the nodes would leak, because NonNull has no destructor.
+        struct DropGuard<'a, T>(&'a mut LinkedList<T>);
+        impl<'a, T> Drop for DropGuard<'a, T> {
+            fn drop(&mut self) {
+                while self.0.pop_front().is_some() {}
+            }
+        }
+
+        while let Some(elem) = self.pop_front() {
+            let guard = DropGuard(self);
+            drop(elem);
+            std::mem::forget(guard);
+        }
     }
 }
 
@@ -201,7 +219,7 @@ impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
 }
 
 impl<T> LinkedList<T> {
-    pub fn iter(&self) -> Iter<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
         Iter {
             front: self.front,
             back: self.back,
@@ -226,66 +244,1381 @@ impl<'a, T> Iterator for IterMut<'a, T> {
             return None;
         }
 
-        return self.front.map(|mut node| unsafe {
-            self.len -= 1;
-            self.front = node.as_ref().next;
-            &mut node.as_mut().elem
-        });
+        return self.front.map(|mut node| unsafe {
+            self.len -= 1;
+            self.front = node.as_ref().next;
+            &mut node.as_mut().elem
+        });
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        return self.back.map(|mut node| unsafe {
+            self.back = node.as_ref().prev;
+            &mut node.as_mut().elem
+        });
+    }
+}
+
+impl<T> LinkedList<T> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            front: self.front,
+            back: self.back,
+            len: self.len,
+            _boo: std::marker::PhantomData,
+        }
+    }
+}
+
+// into iterator
+pub struct IntoIter<T>(LinkedList<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> LinkedList<T> {
+    pub fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.pop_back()
+    }
+}
+
+// the iterators count their elements, so they all know their exact size
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+// and once they ran out they stay empty
+impl<'a, T> std::iter::FusedIterator for Iter<'a, T> {}
+impl<'a, T> std::iter::FusedIterator for IterMut<'a, T> {}
+impl<T> std::iter::FusedIterator for IntoIter<T> {}
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter { ..*self }
+    }
+}
+
+// the rest of std's LinkedList
+impl<T> LinkedList<T> {
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|elem| elem == x)
+    }
+
+    // drops every element, panic-safe like Drop
+    pub fn clear(&mut self) {
+        drop(std::mem::take(self));
+    }
+
+    // moves all the nodes of `other` to the back, in O(1)
+    pub fn append(&mut self, other: &mut Self) {
+        if let Some(other_front) = other.front.take() {
+            unsafe {
+                (*other_front.as_ptr()).prev = self.back;
+                match self.back {
+                    Some(back) => (*back.as_ptr()).next = Some(other_front),
+                    None => self.front = Some(other_front),
+                }
+            }
+            self.back = other.back.take();
+            self.len += std::mem::take(&mut other.len);
+        }
+    }
+
+    // everything from `at` on, panics if at > len
+    pub fn split_off(&mut self, at: usize) -> LinkedList<T> {
+        assert!(at <= self.len, "cannot split off at a nonexistent index");
+        self.split_nodes(at)
+    }
+
+    // panics if at >= len
+    pub fn remove(&mut self, at: usize) -> T {
+        assert!(
+            at < self.len,
+            "cannot remove at an index >= length: {} >= {}",
+            at,
+            self.len
+        );
+        unsafe {
+            let node = self.node_at(at);
+            self.unlink_node(node);
+            Box::from_raw(node.as_ptr()).elem
+        }
+    }
+
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        self.retain_mut(|elem| f(elem));
+    }
+
+    pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, mut f: F) {
+        let mut cur = self.front;
+        while let Some(node) = cur {
+            unsafe {
+                cur = (*node.as_ptr()).next;
+                if !f(&mut (*node.as_ptr()).elem) {
+                    // unlinked before the element is dropped, in case that panics
+                    self.unlink_node(node);
+                    drop(Box::from_raw(node.as_ptr()));
+                }
+            }
+        }
+    }
+
+    // unlinks `node`, without freeing it
+    unsafe fn unlink_node(&mut self, node: std::ptr::NonNull<Node<T>>) {
+        let prev = (*node.as_ptr()).prev.take();
+        let next = (*node.as_ptr()).next.take();
+        match prev {
+            Some(prev) => (*prev.as_ptr()).next = next,
+            None => self.front = next,
+        }
+        match next {
+            Some(next) => (*next.as_ptr()).prev = prev,
+            None => self.back = prev,
+        }
+        self.len -= 1;
+    }
+
+    // links the nodes of `other` in between `prev` and `next`, which must be
+    // neighbours (None for the ends)
+    fn splice_nodes(&mut self, prev: Link<T>, next: Link<T>, mut other: LinkedList<T>) {
+        let (front, back) = match (other.front.take(), other.back.take()) {
+            (Some(front), Some(back)) => (front, back),
+            _ => return,
+        };
+        unsafe {
+            (*front.as_ptr()).prev = prev;
+            (*back.as_ptr()).next = next;
+            match prev {
+                Some(prev) => (*prev.as_ptr()).next = Some(front),
+                None => self.front = Some(front),
+            }
+            match next {
+                Some(next) => (*next.as_ptr()).prev = Some(back),
+                None => self.back = Some(back),
+            }
+        }
+        self.len += std::mem::take(&mut other.len);
+    }
+}
+
+impl<T> LinkedList<T> {
+    // (start, end) of `range`, panics like slice indexing does
+    fn bounds<R: std::ops::RangeBounds<usize>>(&self, range: R) -> (usize, usize) {
+        use std::ops::Bound;
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.checked_add(1).expect("range start overflows"),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.checked_add(1).expect("range end overflows"),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len,
+        };
+        assert!(
+            start <= end,
+            "range start {} is greater than end {}",
+            start,
+            end
+        );
+        assert!(
+            end <= self.len,
+            "range end {} out of bounds for length {}",
+            end,
+            self.len
+        );
+        (start, end)
+    }
+
+    // the node at `index`, which must be < len, walking from the closer end
+    fn node_at(&self, index: usize) -> std::ptr::NonNull<Node<T>> {
+        debug_assert!(index < self.len);
+        unsafe {
+            if index < self.len - index {
+                let mut node = self.front.unwrap();
+                for _ in 0..index {
+                    node = (*node.as_ptr()).next.unwrap();
+                }
+                node
+            } else {
+                let mut node = self.back.unwrap();
+                for _ in index + 1..self.len {
+                    node = (*node.as_ptr()).prev.unwrap();
+                }
+                node
+            }
+        }
+    }
+
+    // front and back node of the non-empty range start..end
+    fn range_links(&self, start: usize, end: usize) -> (Link<T>, Link<T>) {
+        if start == end {
+            return (None, None);
+        }
+        (Some(self.node_at(start)), Some(self.node_at(end - 1)))
+    }
+}
+
+// borrowing iterators over part of the list, they are Iter and IterMut
+// starting from the nodes at both ends of the range
+impl<T> LinkedList<T> {
+    pub fn range<R: std::ops::RangeBounds<usize>>(&self, range: R) -> Iter<'_, T> {
+        let (start, end) = self.bounds(range);
+        let (front, back) = self.range_links(start, end);
+        Iter {
+            front,
+            back,
+            len: end - start,
+            _boo: std::marker::PhantomData,
+        }
+    }
+
+    pub fn range_mut<R: std::ops::RangeBounds<usize>>(&mut self, range: R) -> IterMut<'_, T> {
+        let (start, end) = self.bounds(range);
+        let (front, back) = self.range_links(start, end);
+        IterMut {
+            front,
+            back,
+            len: end - start,
+            _boo: std::marker::PhantomData,
+        }
+    }
+}
+
+// draining iterator
+// The drained range is cut out of the list right away, the list keeps the
+// part before it and the drain holds the part after it. Nodes are freed one
+// by one as they are yielded, and dropping the drain drops whatever is left
+// in the range and links the part after it back. If the drain is leaked the
+// list just loses everything from the range on, it is never left dangling.
+pub struct Drain<'a, T> {
+    list: &'a mut LinkedList<T>,
+    // the range, not yielded yet
+    front: Link<T>,
+    back: Link<T>,
+    len: usize,
+    // the part of the list after the range
+    rest_front: Link<T>,
+    rest_back: Link<T>,
+    rest_len: usize,
+}
+
+impl<T> LinkedList<T> {
+    pub fn drain<R: std::ops::RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T> {
+        let (start, end) = self.bounds(range);
+        let (front, back) = self.range_links(start, end);
+        let (mut rest_front, mut rest_back, mut rest_len) = (None, None, 0);
+        if let (Some(first), Some(last)) = (front, back) {
+            unsafe {
+                let before = (*first.as_ptr()).prev;
+                rest_front = (*last.as_ptr()).next;
+                if rest_front.is_some() {
+                    rest_back = self.back;
+                    rest_len = self.len - end;
+                }
+
+                // the list keeps the part before the range
+                match before {
+                    Some(before) => (*before.as_ptr()).next = None,
+                    None => self.front = None,
+                }
+                self.back = before;
+                self.len = start;
+            }
+        }
+        Drain {
+            list: self,
+            front,
+            back,
+            len: end - start,
+            rest_front,
+            rest_back,
+            rest_len,
+        }
+    }
+}
+
+impl<'a, T> Drain<'a, T> {
+    // puts the part after the range back at the end of the list
+    fn relink(&mut self) {
+        if let Some(rest) = self.rest_front.take() {
+            unsafe {
+                (*rest.as_ptr()).prev = self.list.back;
+                match self.list.back {
+                    Some(back) => (*back.as_ptr()).next = Some(rest),
+                    None => self.list.front = Some(rest),
+                }
+            }
+            self.list.back = self.rest_back;
+            self.list.len += self.rest_len;
+        }
+    }
+}
+
+impl<'a, T> Iterator for Drain<'a, T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        return self.front.map(|node| unsafe {
+            let boxed_node = Box::from_raw(node.as_ptr());
+            self.front = boxed_node.next;
+            boxed_node.elem
+        });
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Drain<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        return self.back.map(|node| unsafe {
+            let boxed_node = Box::from_raw(node.as_ptr());
+            self.back = boxed_node.prev;
+            boxed_node.elem
+        });
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Drain<'a, T> {}
+impl<'a, T> std::iter::FusedIterator for Drain<'a, T> {}
+
+impl<'a, T> Drop for Drain<'a, T> {
+    fn drop(&mut self) {
+        // same panic safety as LinkedList's Drop, and the list gets its
+        // tail back either way
+        struct DropGuard<'r, 'a, T>(&'r mut Drain<'a, T>);
+        impl<'r, 'a, T> Drop for DropGuard<'r, 'a, T> {
+            fn drop(&mut self) {
+                self.0.for_each(drop);
+                self.0.relink();
+            }
+        }
+
+        while let Some(elem) = self.next() {
+            let guard = DropGuard(self);
+            drop(elem);
+            std::mem::forget(guard);
+        }
+        self.relink();
+    }
+}
+
+// node handles, for the lists of this crate built on top of this one
+// A NodeRef is a plain pointer, it must come from the list it is used with
+// and its node must still be in there: that's why the methods taking one are
+// unsafe.
+pub(crate) struct NodeRef<T>(std::ptr::NonNull<Node<T>>);
+
+impl<T> Clone for NodeRef<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for NodeRef<T> {}
+
+impl<T> PartialEq for NodeRef<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T> LinkedList<T> {
+    pub(crate) fn front_node(&self) -> Option<NodeRef<T>> {
+        self.front.map(NodeRef)
+    }
+
+    // the first node matching `pred`, and how many elements were looked at
+    pub(crate) fn find_node<P: FnMut(&T) -> bool>(
+        &self,
+        mut pred: P,
+    ) -> (Option<NodeRef<T>>, usize) {
+        let mut cur = self.front;
+        let mut probes = 0;
+        while let Some(node) = cur {
+            probes += 1;
+            unsafe {
+                if pred(&(*node.as_ptr()).elem) {
+                    return (Some(NodeRef(node)), probes);
+                }
+                cur = (*node.as_ptr()).next;
+            }
+        }
+        (None, probes)
+    }
+
+    pub(crate) unsafe fn node_elem(&self, node: NodeRef<T>) -> &T {
+        &(*node.0.as_ptr()).elem
+    }
+
+    pub(crate) unsafe fn node_elem_mut(&mut self, node: NodeRef<T>) -> &mut T {
+        &mut (*node.0.as_ptr()).elem
+    }
+
+    pub(crate) unsafe fn prev_node(&self, node: NodeRef<T>) -> Option<NodeRef<T>> {
+        (*node.0.as_ptr()).prev.map(NodeRef)
+    }
+
+    // Unlinks `node` and links it back in right before `target`, O(1).
+    pub(crate) unsafe fn move_before(&mut self, node: NodeRef<T>, target: NodeRef<T>) {
+        let (node, target) = (node.0, target.0);
+        if node == target {
+            return;
+        }
+
+        let (prev, next) = ((*node.as_ptr()).prev, (*node.as_ptr()).next);
+        match prev {
+            Some(prev) => (*prev.as_ptr()).next = next,
+            None => self.front = next,
+        }
+        match next {
+            Some(next) => (*next.as_ptr()).prev = prev,
+            None => self.back = prev,
+        }
+
+        let before = (*target.as_ptr()).prev;
+        match before {
+            Some(before) => (*before.as_ptr()).next = Some(node),
+            None => self.front = Some(node),
+        }
+        (*node.as_ptr()).prev = before;
+        (*node.as_ptr()).next = Some(target);
+        (*target.as_ptr()).prev = Some(node);
+    }
+}
+
+// rotation
+// The list is closed into a ring for a moment, `front` and `back` move to
+// their new nodes and the ring is opened again between them. Nothing is
+// allocated or freed, and `node_at` walks from whichever end is closer.
+impl<T> LinkedList<T> {
+    // the element at `n` becomes the front, panics if n > len
+    pub fn rotate_left(&mut self, n: usize) {
+        assert!(
+            n <= self.len,
+            "rotate by {} out of bounds for length {}",
+            n,
+            self.len
+        );
+        if n == 0 || n == self.len {
+            return;
+        }
+        let new_front = self.node_at(n);
+        unsafe {
+            let (front, back) = (self.front.unwrap(), self.back.unwrap());
+            (*back.as_ptr()).next = Some(front);
+            (*front.as_ptr()).prev = Some(back);
+
+            let new_back = (*new_front.as_ptr()).prev.unwrap();
+            (*new_back.as_ptr()).next = None;
+            (*new_front.as_ptr()).prev = None;
+            self.front = Some(new_front);
+            self.back = Some(new_back);
+        }
+    }
+
+    // the element at `len - n` becomes the front, panics if n > len
+    pub fn rotate_right(&mut self, n: usize) {
+        assert!(
+            n <= self.len,
+            "rotate by {} out of bounds for length {}",
+            n,
+            self.len
+        );
+        self.rotate_left(self.len - n);
+    }
+}
+
+// sorted lists
+// These expect the list to be sorted (and `other` too). If it isn't, the
+// result is some order of the same elements, never anything unsound. Nodes
+// are only ever relinked: the set operations move the nodes they keep from
+// both lists into the result, and free the others.
+impl<T> LinkedList<T> {
+    pub fn insert_sorted(&mut self, elem: T)
+    where
+        T: Ord,
+    {
+        self.insert_sorted_by(elem, T::cmp);
+    }
+
+    // Inserts `elem` after every element not greater than it. The position is
+    // searched from both ends at once, so finding it takes O(min(i, len - i)).
+    pub fn insert_sorted_by<F>(&mut self, elem: T, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> std::cmp::Ordering,
+    {
+        use std::cmp::Ordering::Less;
+        let mut front = self.front;
+        let mut back = self.back;
+        // the node to insert after, None for the front
+        let after = unsafe {
+            loop {
+                match (front, back) {
+                    (Some(f), Some(b)) => {
+                        if compare(&elem, &(*f.as_ptr()).elem) == Less {
+                            break (*f.as_ptr()).prev;
+                        }
+                        if compare(&elem, &(*b.as_ptr()).elem) != Less {
+                            break Some(b);
+                        }
+                        front = (*f.as_ptr()).next;
+                        back = (*b.as_ptr()).prev;
+                    }
+                    _ => break self.back,
+                }
+            }
+        };
+
+        match after {
+            None => self.push_front(elem),
+            Some(after) => unsafe {
+                let next = (*after.as_ptr()).next;
+                let new = std::ptr::NonNull::new_unchecked(Box::into_raw(Box::new(Node {
+                    prev: Some(after),
+                    next,
+                    elem,
+                })));
+                (*after.as_ptr()).next = Some(new);
+                match next {
+                    Some(next) => (*next.as_ptr()).prev = Some(new),
+                    None => self.back = Some(new),
+                }
+                self.len += 1;
+            },
+        }
+    }
+
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b);
+    }
+
+    pub fn dedup_by_key<K, F>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut T) -> K,
+        K: PartialEq,
+    {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    // Removes every element for which `same_bucket(elem, kept)` is true, where
+    // `kept` is the last element before it that was not removed, like Vec.
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        let mut kept = match self.front {
+            Some(front) => front,
+            None => return,
+        };
+        unsafe {
+            while let Some(node) = (*kept.as_ptr()).next {
+                if !same_bucket(&mut (*node.as_ptr()).elem, &mut (*kept.as_ptr()).elem) {
+                    kept = node;
+                    continue;
+                }
+                // unlinked before the element is dropped, in case that panics
+                let boxed_node = Box::from_raw(node.as_ptr());
+                (*kept.as_ptr()).next = boxed_node.next;
+                match boxed_node.next {
+                    Some(next) => (*next.as_ptr()).prev = Some(kept),
+                    None => self.back = Some(kept),
+                }
+                self.len -= 1;
+                drop(boxed_node);
+            }
+        }
+    }
+
+    // Everything in either list, an element of `other` is dropped if it is
+    // matched by an equal one here.
+    pub fn union(&mut self, other: LinkedList<T>)
+    where
+        T: Ord,
+    {
+        self.merge_sorted(other, SetOp::Union);
+    }
+
+    // The elements matched by an equal element in `other`.
+    pub fn intersection(&mut self, other: LinkedList<T>)
+    where
+        T: Ord,
+    {
+        self.merge_sorted(other, SetOp::Intersection);
+    }
+
+    // The elements not matched by an equal element in `other`.
+    pub fn difference(&mut self, other: LinkedList<T>)
+    where
+        T: Ord,
+    {
+        self.merge_sorted(other, SetOp::Difference);
+    }
+
+    // Walks both lists in order, every element of one list is matched with at
+    // most one equal element of the other, so duplicates count. The result is
+    // built in `self` from the nodes taken off the front of both lists.
+    fn merge_sorted(&mut self, mut other: LinkedList<T>, op: SetOp)
+    where
+        T: Ord,
+    {
+        use std::cmp::Ordering::{Equal, Greater, Less};
+        let mut ours = std::mem::take(self);
+        loop {
+            let order = match (ours.front, other.front) {
+                (Some(a), Some(b)) => unsafe { (*a.as_ptr()).elem.cmp(&(*b.as_ptr()).elem) },
+                (Some(_), None) => {
+                    if op != SetOp::Intersection {
+                        self.append(&mut ours);
+                    }
+                    return;
+                }
+                (None, Some(_)) => {
+                    if op == SetOp::Union {
+                        self.append(&mut other);
+                    }
+                    return;
+                }
+                (None, None) => return,
+            };
+            match (order, op) {
+                (Less, SetOp::Intersection) => drop(ours.pop_front()),
+                (Less, _) => self.push_back_node(ours.pop_front_node().unwrap()),
+                (Greater, SetOp::Union) => self.push_back_node(other.pop_front_node().unwrap()),
+                (Greater, _) => drop(other.pop_front()),
+                (Equal, SetOp::Difference) => {
+                    drop(ours.pop_front());
+                    drop(other.pop_front());
+                }
+                (Equal, _) => {
+                    self.push_back_node(ours.pop_front_node().unwrap());
+                    drop(other.pop_front());
+                }
+            }
+        }
+    }
+
+    // unlinks the front node, without freeing it
+    fn pop_front_node(&mut self) -> Link<T> {
+        let node = self.front?;
+        unsafe {
+            self.front = (*node.as_ptr()).next.take();
+            match self.front {
+                Some(new_head) => (*new_head.as_ptr()).prev = None,
+                None => self.back = None,
+            }
+        }
+        self.len -= 1;
+        Some(node)
+    }
+
+    // links an unlinked node in at the back
+    fn push_back_node(&mut self, node: std::ptr::NonNull<Node<T>>) {
+        unsafe {
+            (*node.as_ptr()).prev = self.back;
+            match self.back {
+                Some(back) => (*back.as_ptr()).next = Some(node),
+                None => self.front = Some(node),
+            }
+        }
+        self.back = Some(node);
+        self.len += 1;
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SetOp {
+    Union,
+    Intersection,
+    Difference,
+}
+
+// splitting
+// Sublists are cut out of the list by relinking, no element is moved and no
+// node allocated. The pieces own disjoint nodes, so they can go to different
+// threads thanks to the Send impls below.
+impl<T> LinkedList<T> {
+    // cuts the list in two, returns everything from `at` on
+    fn split_nodes(&mut self, at: usize) -> LinkedList<T> {
+        debug_assert!(at <= self.len);
+        let mut back = LinkedList::new();
+        if at == self.len {
+            return back;
+        }
+        if at == 0 {
+            return std::mem::take(self);
+        }
+        let first = self.node_at(at);
+        unsafe {
+            let last = (*first.as_ptr()).prev.take().unwrap();
+            (*last.as_ptr()).next = None;
+            back.front = Some(first);
+            back.back = self.back.replace(last);
+        }
+        back.len = self.len - at;
+        self.len = at;
+        back
+    }
+
+    // the list cut into lists of `size` elements, the last one may be shorter
+    pub fn chunks(self, size: usize) -> Chunks<T> {
+        assert!(size != 0, "chunk size must be non-zero");
+        Chunks { rest: self, size }
+    }
+
+    // two iterators, over the elements before `mid` and from `mid` on
+    pub fn split_at_mut(&mut self, mid: usize) -> (IterMut<'_, T>, IterMut<'_, T>) {
+        assert!(
+            mid <= self.len,
+            "mid {} out of bounds for length {}",
+            mid,
+            self.len
+        );
+        let (front, before_mid) = self.range_links(0, mid);
+        let (at_mid, back) = self.range_links(mid, self.len);
+        (
+            IterMut {
+                front,
+                back: before_mid,
+                len: mid,
+                _boo: std::marker::PhantomData,
+            },
+            IterMut {
+                front: at_mid,
+                back,
+                len: self.len - mid,
+                _boo: std::marker::PhantomData,
+            },
+        )
+    }
+
+    // the elements matching `pred`, and the others, in their original order
+    pub fn partition<F>(mut self, mut pred: F) -> (LinkedList<T>, LinkedList<T>)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut yes = LinkedList::new();
+        let mut no = LinkedList::new();
+        while let Some(front) = self.front() {
+            let matched = pred(front);
+            let node = self.pop_front_node().unwrap();
+            if matched {
+                yes.push_back_node(node);
+            } else {
+                no.push_back_node(node);
+            }
+        }
+        (yes, no)
+    }
+}
+
+pub struct Chunks<T> {
+    rest: LinkedList<T>,
+    size: usize,
+}
+
+impl<T> Iterator for Chunks<T> {
+    type Item = LinkedList<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let rest = self.rest.split_nodes(self.size.min(self.rest.len));
+        Some(std::mem::replace(&mut self.rest, rest))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.rest.len.div_ceil(self.size);
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for Chunks<T> {}
+impl<T> std::iter::FusedIterator for Chunks<T> {}
+
+// parallel iteration
+// The list is walked once to cut it into parts of about the same length, one
+// per scoped thread, and the results are put back together in list order.
+// How many parts only depends on the length, so even results that depend on
+// the grouping, like floating point sums, are the same on every machine.
+const PAR_MIN_PART: usize = 1024;
+const PAR_MAX_PARTS: usize = 16;
+
+impl<T> LinkedList<T> {
+    // (front, back, len) of each part, front to back
+    fn par_parts(&self) -> Vec<(Link<T>, Link<T>, usize)> {
+        let parts = (self.len / PAR_MIN_PART).clamp(1, PAR_MAX_PARTS);
+        let mut result = Vec::with_capacity(parts);
+        let mut cur = self.front;
+        for part in 0..parts {
+            let len = (part + 1) * self.len / parts - part * self.len / parts;
+            let front = cur;
+            let mut back = None;
+            for _ in 0..len {
+                let node = cur.unwrap();
+                back = Some(node);
+                cur = unsafe { (*node.as_ptr()).next };
+            }
+            result.push((front, back, len));
+        }
+        result
+    }
+
+    pub fn par_for_each<F>(&mut self, f: F)
+    where
+        T: Send,
+        F: Fn(&mut T) + Sync,
+    {
+        let parts = self.par_parts();
+        let f = &f;
+        // every part borrows the list mutably, but their nodes are disjoint
+        let iters = parts
+            .into_iter()
+            .map(|(front, back, len)| IterMut::<'_, T> {
+                front,
+                back,
+                len,
+                _boo: std::marker::PhantomData,
+            });
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = iters
+                .map(|iter| scope.spawn(move || iter.for_each(f)))
+                .collect();
+            for worker in workers {
+                join(worker);
+            }
+        });
+    }
+
+    pub fn par_map_collect<U, F>(&self, f: F) -> LinkedList<U>
+    where
+        T: Sync,
+        U: Send,
+        F: Fn(&T) -> U + Sync,
+    {
+        let f = &f;
+        let mut result = LinkedList::new();
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = self
+                .par_iters()
+                .map(|iter| scope.spawn(move || iter.map(f).collect::<LinkedList<U>>()))
+                .collect();
+            for worker in workers {
+                result.append(&mut join(worker));
+            }
+        });
+        result
+    }
+
+    // each part is summed on its own, then the partial sums in order
+    pub fn par_sum<'a, S>(&'a self) -> S
+    where
+        T: Sync,
+        S: std::iter::Sum<&'a T> + std::iter::Sum<S> + Send,
+    {
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = self
+                .par_iters()
+                .map(|iter| scope.spawn(move || iter.sum::<S>()))
+                .collect();
+            workers.into_iter().map(join).sum()
+        })
+    }
+
+    fn par_iters(&self) -> impl Iterator<Item = Iter<'_, T>> {
+        self.par_parts().into_iter().map(|(front, back, len)| Iter {
+            front,
+            back,
+            len,
+            _boo: std::marker::PhantomData,
+        })
+    }
+}
+
+// the result of a worker, its panic goes on in this thread
+fn join<R>(worker: std::thread::ScopedJoinHandle<'_, R>) -> R {
+    worker
+        .join()
+        .unwrap_or_else(|payload| std::panic::resume_unwind(payload))
+}
+
+// cursors
+// The (unstable) cursors of std's LinkedList. A cursor points at an element,
+// or at the "ghost" position between the back and the front, whose index is
+// `len`. Moving past either end goes to the ghost, and from there to the other
+// end. CursorMut can edit the list around it in O(1), splicing and splitting
+// relink whole lists at once.
+pub struct Cursor<'a, T> {
+    list: &'a LinkedList<T>,
+    cur: Link<T>,
+    index: usize,
+}
+
+pub struct CursorMut<'a, T> {
+    list: &'a mut LinkedList<T>,
+    cur: Link<T>,
+    index: usize,
+}
+
+impl<T> LinkedList<T> {
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        Cursor {
+            list: self,
+            cur: self.front,
+            index: 0,
+        }
+    }
+
+    pub fn cursor_back(&self) -> Cursor<'_, T> {
+        Cursor {
+            list: self,
+            cur: self.back,
+            index: self.len.saturating_sub(1),
+        }
+    }
+
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            cur: self.front,
+            list: self,
+            index: 0,
+        }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            cur: self.back,
+            index: self.len.saturating_sub(1),
+            list: self,
+        }
+    }
+
+    // the position after (cur, index), the same for both cursors
+    fn cursor_next(&self, cur: Link<T>, index: usize) -> (Link<T>, usize) {
+        match cur {
+            Some(cur) => unsafe { ((*cur.as_ptr()).next, index + 1) },
+            None => (self.front, 0),
+        }
+    }
+
+    fn cursor_prev(&self, cur: Link<T>, index: usize) -> (Link<T>, usize) {
+        match cur {
+            Some(cur) => unsafe {
+                match (*cur.as_ptr()).prev {
+                    Some(prev) => (Some(prev), index - 1),
+                    None => (None, self.len),
+                }
+            },
+            None => (self.back, self.len.saturating_sub(1)),
+        }
+    }
+}
+
+impl<'a, T> Cursor<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.cur.map(|_| self.index)
+    }
+
+    pub fn move_next(&mut self) {
+        (self.cur, self.index) = self.list.cursor_next(self.cur, self.index);
+    }
+
+    pub fn move_prev(&mut self) {
+        (self.cur, self.index) = self.list.cursor_prev(self.cur, self.index);
+    }
+
+    pub fn current(&self) -> Option<&'a T> {
+        self.cur.map(|node| unsafe { &(*node.as_ptr()).elem })
+    }
+
+    pub fn peek_next(&self) -> Option<&'a T> {
+        let (next, _) = self.list.cursor_next(self.cur, self.index);
+        next.map(|node| unsafe { &(*node.as_ptr()).elem })
+    }
+
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        let (prev, _) = self.list.cursor_prev(self.cur, self.index);
+        prev.map(|node| unsafe { &(*node.as_ptr()).elem })
+    }
+
+    pub fn front(&self) -> Option<&'a T> {
+        self.list.front()
+    }
+
+    pub fn back(&self) -> Option<&'a T> {
+        self.list.back()
+    }
+
+    pub fn as_list(&self) -> &'a LinkedList<T> {
+        self.list
+    }
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.cur.map(|_| self.index)
+    }
+
+    pub fn move_next(&mut self) {
+        (self.cur, self.index) = self.list.cursor_next(self.cur, self.index);
+    }
+
+    pub fn move_prev(&mut self) {
+        (self.cur, self.index) = self.list.cursor_prev(self.cur, self.index);
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.cur.map(|node| unsafe { &mut (*node.as_ptr()).elem })
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let (next, _) = self.list.cursor_next(self.cur, self.index);
+        next.map(|node| unsafe { &mut (*node.as_ptr()).elem })
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let (prev, _) = self.list.cursor_prev(self.cur, self.index);
+        prev.map(|node| unsafe { &mut (*node.as_ptr()).elem })
+    }
+
+    // a read-only cursor at the same position, while this one is borrowed
+    pub fn as_cursor(&self) -> Cursor<'_, T> {
+        Cursor {
+            list: self.list,
+            cur: self.cur,
+            index: self.index,
+        }
+    }
+
+    pub fn as_list(&self) -> &LinkedList<T> {
+        self.list
+    }
+
+    // after the ghost is the front
+    pub fn insert_after(&mut self, elem: T) {
+        self.splice_after(LinkedList::from([elem]));
+    }
+
+    // before the ghost is the back
+    pub fn insert_before(&mut self, elem: T) {
+        self.splice_before(LinkedList::from([elem]));
+    }
+
+    pub fn splice_after(&mut self, list: LinkedList<T>) {
+        let (prev, next) = match self.cur {
+            Some(cur) => (Some(cur), unsafe { (*cur.as_ptr()).next }),
+            None => (None, self.list.front),
+        };
+        if self.cur.is_none() {
+            self.index += list.len;
+        }
+        self.list.splice_nodes(prev, next, list);
+    }
+
+    pub fn splice_before(&mut self, list: LinkedList<T>) {
+        let (prev, next) = match self.cur {
+            Some(cur) => (unsafe { (*cur.as_ptr()).prev }, Some(cur)),
+            None => (self.list.back, None),
+        };
+        self.index += list.len;
+        self.list.splice_nodes(prev, next, list);
+    }
+
+    // removes the current element, the cursor moves on to the next one
+    pub fn remove_current(&mut self) -> Option<T> {
+        self.remove_current_as_list()?.pop_front()
+    }
+
+    // like remove_current, but the node is kept, as a list of one
+    pub fn remove_current_as_list(&mut self) -> Option<LinkedList<T>> {
+        let node = self.cur?;
+        unsafe {
+            self.cur = (*node.as_ptr()).next;
+            self.list.unlink_node(node);
+        }
+        let mut removed = LinkedList::new();
+        removed.front = Some(node);
+        removed.back = Some(node);
+        removed.len = 1;
+        Some(removed)
+    }
+
+    // cuts the list after the current element, from the ghost that's all of it
+    pub fn split_after(&mut self) -> LinkedList<T> {
+        let cur = match self.cur {
+            Some(cur) => cur,
+            None => {
+                self.index = 0;
+                return std::mem::take(self.list);
+            }
+        };
+        let mut after = LinkedList::new();
+        unsafe {
+            if let Some(next) = (*cur.as_ptr()).next.take() {
+                (*next.as_ptr()).prev = None;
+                after.front = Some(next);
+                after.back = self.list.back.replace(cur);
+                after.len = self.list.len - self.index - 1;
+                self.list.len = self.index + 1;
+            }
+        }
+        after
+    }
+
+    // cuts the list before the current element, from the ghost that's all of it
+    pub fn split_before(&mut self) -> LinkedList<T> {
+        let cur = match self.cur {
+            Some(cur) => cur,
+            None => {
+                self.index = 0;
+                return std::mem::take(self.list);
+            }
+        };
+        let mut before = LinkedList::new();
+        unsafe {
+            if let Some(prev) = (*cur.as_ptr()).prev.take() {
+                (*prev.as_ptr()).next = None;
+                before.front = self.list.front.replace(cur);
+                before.back = Some(prev);
+                before.len = self.index;
+                self.list.len -= self.index;
+                self.index = 0;
+            }
+        }
+        before
+    }
+
+    pub fn push_front(&mut self, elem: T) {
+        self.list.push_front(elem);
+        self.index += 1;
+    }
+
+    pub fn push_back(&mut self, elem: T) {
+        self.list.push_back(elem);
+        if self.cur.is_none() {
+            self.index += 1;
+        }
+    }
+
+    // if the cursor is on the front element it moves on to the next one
+    pub fn pop_front(&mut self) -> Option<T> {
+        let front = self.list.front?;
+        if self.cur == Some(front) {
+            self.cur = unsafe { (*front.as_ptr()).next };
+        } else {
+            self.index -= 1;
+        }
+        self.list.pop_front()
+    }
+
+    // if the cursor is on the back element it moves on to the ghost
+    pub fn pop_back(&mut self) -> Option<T> {
+        let back = self.list.back?;
+        if self.cur == Some(back) || self.cur.is_none() {
+            self.cur = None;
+            self.index = self.list.len - 1;
+        }
+        self.list.pop_back()
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.list.front()
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.list.front_mut()
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.list.back()
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.list.back_mut()
     }
+}
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.len, Some(self.len))
+impl<'a, T> Clone for Cursor<'a, T> {
+    fn clone(&self) -> Self {
+        Cursor { ..*self }
     }
 }
 
-impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
-    fn next_back(&mut self) -> Option<Self::Item> {
-        if self.len == 0 {
-            return None;
-        }
+impl<'a, T: std::fmt::Debug> std::fmt::Debug for Cursor<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Cursor")
+            .field(self.list)
+            .field(&self.index())
+            .finish()
+    }
+}
 
-        self.len -= 1;
-        return self.back.map(|mut node| unsafe {
-            self.back = node.as_ref().prev;
-            &mut node.as_mut().elem
-        });
+impl<'a, T: std::fmt::Debug> std::fmt::Debug for CursorMut<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("CursorMut")
+            .field(self.list)
+            .field(&self.index())
+            .finish()
     }
 }
 
+// circular cursor
+// A cursor that goes from the back straight to the front and the other way
+// round, there is no "ghost" position in between. It only points nowhere when
+// the list is empty.
+pub struct CircularCursor<'a, T> {
+    list: &'a mut LinkedList<T>,
+    cur: Link<T>,
+    index: usize,
+}
+
 impl<T> LinkedList<T> {
-    pub fn iter_mut(&self) -> IterMut<T> {
-        IterMut {
-            front: self.front,
-            back: self.back,
-            len: self.len,
-            _boo: std::marker::PhantomData,
+    pub fn circular_cursor(&mut self) -> CircularCursor<'_, T> {
+        CircularCursor {
+            cur: self.front,
+            list: self,
+            index: 0,
         }
     }
 }
 
-// into iterator
-pub struct IntoIter<T>(LinkedList<T>);
+impl<'a, T> CircularCursor<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.cur.map(|_| self.index)
+    }
 
-impl<T> Iterator for IntoIter<T> {
-    type Item = T;
-    fn next(&mut self) -> Option<Self::Item> {
-        self.0.pop_front()
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.cur.map(|node| unsafe { &mut (*node.as_ptr()).elem })
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.0.len, Some(self.0.len))
+    pub fn move_next(&mut self) {
+        if let Some(cur) = self.cur {
+            unsafe {
+                match (*cur.as_ptr()).next {
+                    Some(next) => {
+                        self.cur = Some(next);
+                        self.index += 1;
+                    }
+                    None => {
+                        self.cur = self.list.front;
+                        self.index = 0;
+                    }
+                }
+            }
+        }
     }
-}
 
-impl<T> LinkedList<T> {
-    pub fn into_iter(self) -> IntoIter<T> {
-        IntoIter { 0: self }
+    pub fn move_prev(&mut self) {
+        if let Some(cur) = self.cur {
+            unsafe {
+                match (*cur.as_ptr()).prev {
+                    Some(prev) => {
+                        self.cur = Some(prev);
+                        self.index -= 1;
+                    }
+                    None => {
+                        self.cur = self.list.back;
+                        self.index = self.list.len - 1;
+                    }
+                }
+            }
+        }
     }
-}
 
-impl<T> DoubleEndedIterator for IntoIter<T> {
-    fn next_back(&mut self) -> Option<Self::Item> {
-        self.0.pop_back()
+    // Inserts `elem` just before the current element in the ring, the cursor
+    // stays where it is. Before the front that means at the back. On an
+    // empty list the new element becomes the current one.
+    pub fn insert_before(&mut self, elem: T) {
+        let cur = match self.cur {
+            Some(cur) => cur,
+            None => {
+                self.list.push_back(elem);
+                self.cur = self.list.front;
+                return;
+            }
+        };
+        unsafe {
+            match (*cur.as_ptr()).prev {
+                Some(prev) => {
+                    let new = std::ptr::NonNull::new_unchecked(Box::into_raw(Box::new(Node {
+                        prev: Some(prev),
+                        next: Some(cur),
+                        elem,
+                    })));
+                    (*prev.as_ptr()).next = Some(new);
+                    (*cur.as_ptr()).prev = Some(new);
+                    self.list.len += 1;
+                    self.index += 1;
+                }
+                None => self.list.push_back(elem),
+            }
+        }
+    }
+
+    // Removes the current element, the cursor moves on to the next one,
+    // wrapping around to the front.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.cur?;
+        unsafe {
+            let boxed_node = Box::from_raw(node.as_ptr());
+            match boxed_node.prev {
+                Some(prev) => (*prev.as_ptr()).next = boxed_node.next,
+                None => self.list.front = boxed_node.next,
+            }
+            match boxed_node.next {
+                Some(next) => (*next.as_ptr()).prev = boxed_node.prev,
+                None => self.list.back = boxed_node.prev,
+            }
+            self.list.len -= 1;
+
+            self.cur = boxed_node.next;
+            if self.cur.is_none() {
+                self.cur = self.list.front;
+                self.index = 0;
+            }
+            Some(boxed_node.elem)
+        }
     }
 }
 
@@ -293,6 +1626,12 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
 // other interfaces //
 //////////////////////
 
+impl<T> Default for LinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T: std::hash::Hash> std::hash::Hash for LinkedList<T> {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.len().hash(state);
@@ -326,6 +1665,42 @@ impl<T> Extend<T> for LinkedList<T> {
     }
 }
 
+impl<'a, T: Copy + 'a> Extend<&'a T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for LinkedList<T> {
+    fn from(array: [T; N]) -> Self {
+        Self::from_iter(array)
+    }
+}
+
+impl<T> IntoIterator for LinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+    fn into_iter(self) -> IntoIter<T> {
+        self.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
 impl<T> FromIterator<T> for LinkedList<T> {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
         let mut list = Self::new();
@@ -339,10 +1714,6 @@ impl<T: PartialEq> PartialEq for LinkedList<T> {
     fn eq(&self, other: &Self) -> bool {
         self.len() == other.len() && self.iter().eq(other.iter())
     }
-
-    fn ne(&self, other: &Self) -> bool {
-        self.eq(other) == false
-    }
 }
 impl<T: Eq> Eq for LinkedList<T> {}
 
@@ -361,15 +1732,78 @@ impl<T: Ord> Ord for LinkedList<T> {
 unsafe impl<T: Send> Send for LinkedList<T> {}
 unsafe impl<T: Sync> Sync for LinkedList<T> {}
 
-unsafe impl<'a, T: Send> Send for Iter<'a, T> {}
+// Iter only hands out &T, so sending it is sharing the elements, like &T
+unsafe impl<'a, T: Sync> Send for Iter<'a, T> {}
 unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
 
 unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
 unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
 
+unsafe impl<'a, T: Send> Send for Drain<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for Drain<'a, T> {}
+
+// a Cursor is a shared borrow, a CursorMut a unique one, like the iterators
+unsafe impl<'a, T: Sync> Send for Cursor<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for Cursor<'a, T> {}
+
+unsafe impl<'a, T: Send> Send for CursorMut<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for CursorMut<'a, T> {}
+
+unsafe impl<'a, T: Send> Send for CircularCursor<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for CircularCursor<'a, T> {}
+
+// test Sync and Send traits
+#[allow(dead_code)]
+fn assert_properties() {
+    fn is_send<T: Send>() {}
+    fn is_sync<T: Sync>() {}
+
+    is_send::<LinkedList<i32>>();
+    is_sync::<LinkedList<i32>>();
+
+    is_send::<IntoIter<i32>>();
+    is_sync::<IntoIter<i32>>();
+
+    is_send::<Iter<i32>>();
+    is_sync::<Iter<i32>>();
+
+    is_send::<IterMut<i32>>();
+    is_sync::<IterMut<i32>>();
+
+    is_send::<Drain<i32>>();
+    is_sync::<Drain<i32>>();
+
+    is_send::<CircularCursor<i32>>();
+    is_sync::<CircularCursor<i32>>();
+
+    is_send::<Chunks<i32>>();
+    is_sync::<Chunks<i32>>();
+
+    is_send::<Cursor<i32>>();
+    is_sync::<Cursor<i32>>();
+
+    is_send::<CursorMut<i32>>();
+    is_sync::<CursorMut<i32>>();
+
+    fn linked_list_covariant<'a, T>(x: LinkedList<&'static T>) -> LinkedList<&'a T> {
+        x
+    }
+    fn iter_covariant<'i, 'a, T>(x: Iter<'i, &'static T>) -> Iter<'i, &'a T> {
+        x
+    }
+    fn into_iter_covariant<'a, T>(x: IntoIter<&'static T>) -> IntoIter<&'a T> {
+        x
+    }
+}
+
+// these tests are ported from std's LinkedList and kept as close to it as possible
 #[cfg(test)]
+#[allow(clippy::manual_next_back, clippy::neg_cmp_op_on_partial_ord)]
+#[allow(clippy::zero_divided_by_zero, clippy::useless_vec)]
 mod test {
     use super::LinkedList;
+    use crate::test_helpers::{live_allocations, panics, total_allocations, DropCounter};
+    use std::collections::VecDeque;
 
     fn generate_test() -> LinkedList<i32> {
         list_from(&[0, 1, 2, 3, 4, 5, 6])
@@ -511,7 +1945,7 @@ mod test {
 
     #[test]
     fn test_mut_iter() {
-        let m = generate_test();
+        let mut m = generate_test();
         let mut len = m.len();
         for (i, elt) in m.iter_mut().enumerate() {
             assert_eq!(i as i32, *elt);
@@ -638,36 +2072,595 @@ mod test {
 
         assert!(map.is_empty());
     }
-}
 
-// test Sync and Send traits
-#[allow(dead_code)]
-fn assert_properties() {
-    fn is_send<T: Send>() {}
-    fn is_sync<T: Sync>() {}
+    #[test]
+    fn test_drop_order() {
+        let counter = DropCounter::new();
+        let mut list = LinkedList::new();
+        list.push_back(counter.track(2));
+        list.push_front(counter.track(1));
+        list.push_back(counter.track(3));
+        list.push_front(counter.track(0));
+        assert_eq!(counter.count(), 0);
+
+        drop(list);
+        assert_eq!(counter.dropped(), vec![0, 1, 2, 3]);
+    }
 
-    is_send::<LinkedList<i32>>();
-    is_sync::<LinkedList<i32>>();
+    #[test]
+    fn test_drop_panic() {
+        let counter = DropCounter::new();
+        let mut list = LinkedList::new();
+        list.push_back(counter.track(0));
+        list.push_back(counter.panic_on_drop(1));
+        list.push_back(counter.track(2));
+        list.push_back(counter.track(3));
+
+        // the nodes after the panicking one are still dropped, exactly once
+        assert!(panics(move || drop(list)));
+        assert_eq!(counter.dropped(), vec![0, 1, 2, 3]);
+    }
 
-    is_send::<IntoIter<i32>>();
-    is_sync::<IntoIter<i32>>();
+    #[test]
+    fn test_clone_panic() {
+        let counter = DropCounter::new();
+        let list: LinkedList<_> = vec![
+            counter.track(0),
+            counter.track(1),
+            counter.panic_on_clone(2),
+            counter.track(3),
+        ]
+        .into_iter()
+        .collect();
+
+        // the half-built clone is dropped, the original is untouched
+        assert!(panics(|| drop(list.clone())));
+        assert_eq!(counter.dropped(), vec![0, 1]);
+        assert_eq!(list.len(), 4);
+
+        drop(list);
+        assert_eq!(counter.dropped(), vec![0, 1, 0, 1, 2, 3]);
+    }
 
-    is_send::<Iter<i32>>();
-    is_sync::<Iter<i32>>();
+    #[test]
+    fn test_extend_panic() {
+        let counter = DropCounter::new();
+        let mut list = LinkedList::new();
+        list.push_back(counter.track(0));
 
-    is_send::<IterMut<i32>>();
-    is_sync::<IterMut<i32>>();
+        let iter = (1..5).map(|id| {
+            if id == 3 {
+                panic!("iterator panicked");
+            }
+            counter.track(id)
+        });
 
-    // is_send::<Cursor<i32>>();
-    // is_sync::<Cursor<i32>>();
+        // elements yielded before the panic stay in the list
+        assert!(panics(|| list.extend(iter)));
+        assert_eq!(counter.count(), 0);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.back().map(|x| x.id), Some(2));
 
-    fn linked_list_covariant<'a, T>(x: LinkedList<&'static T>) -> LinkedList<&'a T> {
-        x
+        drop(list);
+        assert_eq!(counter.dropped(), vec![0, 1, 2]);
     }
-    fn iter_covariant<'i, 'a, T>(x: Iter<'i, &'static T>) -> Iter<'i, &'a T> {
-        x
+
+    #[test]
+    fn test_default_and_extend_ref() {
+        let mut list: LinkedList<i32> = Default::default();
+        assert!(list.is_empty());
+        list.extend(&[1, 2]);
+        list.extend([3].iter());
+        assert_eq!(list, list_from(&[1, 2, 3]));
     }
-    fn into_iter_covariant<'a, T>(x: IntoIter<&'static T>) -> IntoIter<&'a T> {
-        x
+
+    #[test]
+    fn test_range() {
+        let mut list = generate_test();
+        assert_eq!(list.range(2..5).collect::<Vec<_>>(), vec![&2, &3, &4]);
+        assert_eq!(list.range(..=1).rev().collect::<Vec<_>>(), vec![&1, &0]);
+        assert_eq!(list.range(5..).len(), 2);
+        assert_eq!(list.range(3..3).next(), None);
+        assert_eq!(list.range(..).count(), 7);
+
+        // every range, from both ends
+        for start in 0..=7 {
+            for end in start..=7 {
+                let expected: Vec<i32> = (start as i32..end as i32).collect();
+                assert_eq!(
+                    list.range(start..end).copied().collect::<Vec<_>>(),
+                    expected
+                );
+                let mut back: Vec<i32> = list.range(start..end).rev().copied().collect();
+                back.reverse();
+                assert_eq!(back, expected);
+            }
+        }
+
+        for elem in list.range_mut(1..6) {
+            *elem *= 10;
+        }
+        assert_eq!(list, list_from(&[0, 10, 20, 30, 40, 50, 6]));
+        let mut iter = list.range_mut(2..4);
+        assert_eq!(iter.next_back(), Some(&mut 30));
+        assert_eq!(iter.next(), Some(&mut 20));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_range_bounds_panic() {
+        let list = generate_test();
+        assert!(panics(|| {
+            list.range(3..8);
+        }));
+        assert!(panics(|| {
+            #[allow(clippy::reversed_empty_ranges)]
+            list.range(4..3);
+        }));
+        assert!(panics(|| {
+            let mut list = generate_test();
+            list.drain(..=7);
+        }));
+    }
+
+    #[test]
+    fn test_drain() {
+        // every range, drained from the front
+        for start in 0..=7 {
+            for end in start..=7 {
+                let mut list = generate_test();
+                let drained: Vec<i32> = list.drain(start..end).collect();
+                assert_eq!(drained, (start as i32..end as i32).collect::<Vec<_>>());
+                let rest: Vec<i32> = (0..start as i32).chain(end as i32..7).collect();
+                assert_eq!(list, list_from(&rest));
+                assert_eq!(list.len(), rest.len());
+                // the relinked list works at both ends
+                list.push_back(7);
+                list.push_front(-1);
+                assert_eq!(list.iter().rev().count(), rest.len() + 2);
+            }
+        }
+
+        let mut list = generate_test();
+        let mut drain = list.drain(1..6);
+        assert_eq!(drain.len(), 5);
+        assert_eq!(drain.next_back(), Some(5));
+        assert_eq!(drain.next(), Some(1));
+        assert_eq!(drain.len(), 3);
+        assert_eq!(drain.rev().collect::<Vec<_>>(), vec![4, 3, 2]);
+        assert_eq!(list, list_from(&[0, 6]));
+
+        let mut list = generate_test();
+        assert_eq!(
+            list.drain(..).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4, 5, 6]
+        );
+        assert!(list.is_empty());
+        list.push_back(1);
+        assert_eq!(list.front(), Some(&1));
+        assert_eq!(list.back(), Some(&1));
+    }
+
+    #[test]
+    fn test_drain_partial() {
+        let counter = DropCounter::new();
+        let mut list: LinkedList<_> = (0..6).map(|id| counter.track(id)).collect();
+
+        // the elements left in the drain are dropped with it
+        let mut drain = list.drain(1..5);
+        assert_eq!(drain.next().map(|t| t.id), Some(1));
+        assert_eq!(counter.dropped(), vec![1]);
+        drop(drain);
+        assert_eq!(counter.dropped(), vec![1, 2, 3, 4]);
+        assert_eq!(list.iter().map(|t| t.id).collect::<Vec<_>>(), vec![0, 5]);
+        assert_eq!(
+            list.iter().rev().map(|t| t.id).collect::<Vec<_>>(),
+            vec![5, 0]
+        );
+        assert_eq!(list.len(), 2);
+
+        // leaking the drain leaks the range and everything after it
+        let mut list = generate_test();
+        std::mem::forget(list.drain(2..4));
+        assert_eq!(list, list_from(&[0, 1]));
+        list.push_back(2);
+        assert_eq!(list.len(), 3);
+    }
+
+    #[test]
+    fn test_drain_panic() {
+        let counter = DropCounter::new();
+        let mut list = LinkedList::new();
+        list.push_back(counter.track(0));
+        list.push_back(counter.track(1));
+        list.push_back(counter.panic_on_drop(2));
+        list.push_back(counter.track(3));
+        list.push_back(counter.track(4));
+
+        // the rest of the range is still dropped and the tail relinked
+        assert!(panics(|| drop(list.drain(1..4))));
+        assert_eq!(counter.dropped(), vec![1, 2, 3]);
+        assert_eq!(list.iter().map(|t| t.id).collect::<Vec<_>>(), vec![0, 4]);
+        assert_eq!(list.back().map(|t| t.id), Some(4));
+        drop(list);
+        assert_eq!(counter.dropped(), vec![1, 2, 3, 0, 4]);
+    }
+
+    #[test]
+    fn test_rotate() {
+        for len in 0..8 {
+            for n in 0..=len {
+                let mut list: LinkedList<i32> = (0..len as i32).collect();
+                let mut model: VecDeque<i32> = (0..len as i32).collect();
+                list.rotate_left(n);
+                model.rotate_left(n);
+                assert!(list.iter().eq(model.iter()));
+                assert!(list.iter().rev().eq(model.iter().rev()));
+                assert_eq!(list.back(), model.back());
+
+                list.rotate_right(n);
+                model.rotate_right(n);
+                assert!(list.iter().eq(model.iter()));
+                assert!(list.iter().rev().eq(model.iter().rev()));
+            }
+        }
+
+        // no node is freed or allocated, not even freed and allocated again
+        let mut list: LinkedList<i32> = (0..100).collect();
+        let before = total_allocations();
+        list.rotate_left(30);
+        list.rotate_right(90);
+        assert_eq!(total_allocations(), before);
+        assert_eq!(list.front(), Some(&40));
+        assert_eq!(list.back(), Some(&39));
+
+        assert!(panics(|| generate_test().rotate_left(8)));
+        assert!(panics(|| generate_test().rotate_right(8)));
+    }
+
+    #[test]
+    fn test_circular_cursor() {
+        let mut empty = LinkedList::<i32>::new();
+        let mut cursor = empty.circular_cursor();
+        cursor.move_next();
+        cursor.move_prev();
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.remove_current(), None);
+        cursor.insert_before(7);
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.current(), Some(&mut 7));
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 7));
+
+        let mut list = list_from(&[0, 1, 2]);
+        let mut cursor = list.circular_cursor();
+        let mut seen = Vec::new();
+        for _ in 0..7 {
+            seen.push((cursor.index().unwrap(), *cursor.current().unwrap()));
+            cursor.move_next();
+        }
+        assert_eq!(
+            seen,
+            vec![(0, 0), (1, 1), (2, 2), (0, 0), (1, 1), (2, 2), (0, 0)]
+        );
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(2));
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(1));
+        *cursor.current().unwrap() *= 10;
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![0, 10, 2]);
+    }
+
+    #[test]
+    fn test_circular_cursor_edit() {
+        // a round robin: (id, time slices left), every task runs until done
+        let mut list = list_from(&[(0, 2), (1, 1), (2, 3)]);
+        let mut cursor = list.circular_cursor();
+        let mut finished = Vec::new();
+        while let Some((id, left)) = cursor.current() {
+            *left -= 1;
+            if *left == 0 {
+                finished.push(*id);
+                cursor.remove_current();
+            } else {
+                cursor.move_next();
+            }
+        }
+        assert_eq!(finished, vec![1, 0, 2]);
+        assert!(list.is_empty());
+        assert_eq!(list.front(), None);
+        assert_eq!(list.back(), None);
+
+        let mut list = list_from(&[0, 1, 2]);
+        let mut cursor = list.circular_cursor();
+        // before the front is at the back
+        cursor.insert_before(3);
+        assert_eq!(cursor.index(), Some(0));
+        cursor.move_next();
+        cursor.insert_before(4);
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 1));
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(3));
+        // removing the back wraps around
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.current(), Some(&mut 0));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![0, 4, 1, 2]);
+        assert!(list.iter().rev().eq([2, 1, 4, 0].iter()));
+        assert_eq!(list.len(), 4);
+    }
+
+    #[test]
+    fn test_insert_sorted() {
+        let mut list = LinkedList::new();
+        let mut model = Vec::new();
+        let mut seed = 0x9e37_79b9_7f4a_7c15u64;
+        for _ in 0..500 {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            let value = (seed % 100) as i32;
+            list.insert_sorted(value);
+            let at = model.partition_point(|&x| x <= value);
+            model.insert(at, value);
+        }
+        assert!(list.iter().eq(model.iter()));
+        assert!(list.iter().rev().eq(model.iter().rev()));
+        assert_eq!(list.len(), 500);
+
+        // equal keys keep the order they were inserted in
+        let mut list = LinkedList::new();
+        for (i, key) in [3, 1, 3, 2, 1, 3].into_iter().enumerate() {
+            list.insert_sorted_by((key, i), |a, b| a.0.cmp(&b.0));
+        }
+        assert_eq!(
+            list.into_iter().collect::<Vec<_>>(),
+            vec![(1, 1), (1, 4), (2, 3), (3, 0), (3, 2), (3, 5)]
+        );
+    }
+
+    #[test]
+    fn test_dedup() {
+        let mut list = list_from(&[1, 1, 2, 3, 3, 3, 1, 4, 4]);
+        list.dedup();
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3, 1, 4]
+        );
+        assert_eq!(list.back(), Some(&4));
+        assert!(list.iter().rev().eq([4, 1, 3, 2, 1].iter()));
+        assert_eq!(list.len(), 5);
+
+        let mut list = list_from(&[10, 11, 20, 35, 31, 30, 42]);
+        list.dedup_by_key(|x| *x / 10);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![10, 20, 35, 42]
+        );
+
+        // `b` is the kept element, like Vec::dedup_by
+        let mut list = list_from(&[1, 2, 3, 10, 11]);
+        list.dedup_by(|a, b| *a - *b < 3);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 10]);
+
+        let mut empty = LinkedList::<i32>::new();
+        empty.dedup();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_set_ops() {
+        let a = [1, 2, 2, 2, 4, 6, 7];
+        let b = [0, 2, 2, 5, 6, 8];
+        // the op, its result, and its result with an empty list on either side
+        type Op = fn(&mut LinkedList<i32>, LinkedList<i32>);
+        let ops: [(Op, Vec<i32>, usize, usize); 3] = [
+            (LinkedList::union, vec![0, 1, 2, 2, 2, 4, 5, 6, 7, 8], 6, 7),
+            (LinkedList::intersection, vec![2, 2, 6], 0, 0),
+            (LinkedList::difference, vec![1, 2, 4, 7], 0, 7),
+        ];
+        for (op, expected, empty_ours, empty_theirs) in ops {
+            let mut list = list_from(&a);
+            op(&mut list, list_from(&b));
+            assert!(list.iter().eq(expected.iter()));
+            assert!(list.iter().rev().eq(expected.iter().rev()));
+            assert_eq!(list.len(), expected.len());
+
+            let mut list = LinkedList::new();
+            op(&mut list, list_from(&b));
+            assert_eq!(list.len(), empty_ours);
+            let mut list = list_from(&a);
+            op(&mut list, LinkedList::new());
+            assert_eq!(list.len(), empty_theirs);
+        }
+
+        // nodes are moved, never allocated, and only the dropped ones freed
+        let mut list = list_from(&a);
+        let other = list_from(&b);
+        let before = live_allocations();
+        list.union(other);
+        assert_eq!(before - live_allocations(), 3);
+    }
+
+    #[test]
+    fn test_set_ops_drop() {
+        // the count goes back to 1 once a list dropped its clone
+        let ours: Vec<_> = (0..3).map(std::rc::Rc::new).collect();
+        let theirs: Vec<_> = (1..4).map(std::rc::Rc::new).collect();
+        let mut list: LinkedList<_> = ours.iter().cloned().collect();
+        list.intersection(theirs.iter().cloned().collect());
+        assert!(list.iter().eq(ours[1..].iter()));
+        let counts =
+            |v: &[std::rc::Rc<i32>]| v.iter().map(std::rc::Rc::strong_count).collect::<Vec<_>>();
+        assert_eq!(counts(&ours), vec![1, 2, 2]);
+        assert_eq!(counts(&theirs), vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn test_chunks() {
+        for len in 0..10 {
+            for size in 1..5 {
+                let list: LinkedList<i32> = (0..len).collect();
+                let model: Vec<i32> = (0..len).collect();
+                let chunks = list.chunks(size);
+                assert_eq!(chunks.len(), model.chunks(size).len());
+                for (chunk, expected) in chunks.zip(model.chunks(size)) {
+                    assert!(chunk.iter().eq(expected.iter()));
+                    assert!(chunk.iter().rev().eq(expected.iter().rev()));
+                    assert_eq!(chunk.len(), expected.len());
+                }
+            }
+        }
+
+        // the chunks are the old nodes
+        let list: LinkedList<i32> = (0..100).collect();
+        let mut chunks = Vec::with_capacity(15);
+        let before = live_allocations();
+        chunks.extend(list.chunks(7));
+        assert_eq!(live_allocations(), before);
+
+        // and each can go to its own thread
+        let sums: Vec<i32> = std::thread::scope(|scope| {
+            let workers: Vec<_> = chunks
+                .into_iter()
+                .map(|chunk| scope.spawn(move || chunk.into_iter().sum::<i32>()))
+                .collect();
+            workers.into_iter().map(|w| w.join().unwrap()).collect()
+        });
+        assert_eq!(sums.len(), 15);
+        assert_eq!(sums.iter().sum::<i32>(), (0..100).sum());
+
+        // unused chunks are dropped with the iterator
+        let counter = DropCounter::new();
+        let list: LinkedList<_> = (0..5).map(|id| counter.track(id)).collect();
+        let mut chunks = list.chunks(2);
+        drop(chunks.next());
+        assert_eq!(counter.count(), 2);
+        drop(chunks);
+        assert_eq!(counter.count(), 5);
+
+        assert!(panics(|| drop(generate_test().chunks(0))));
+    }
+
+    #[test]
+    fn test_split_at_mut() {
+        let mut list = generate_test();
+        let (front, back) = list.split_at_mut(3);
+        assert_eq!(front.len(), 3);
+        assert_eq!(back.len(), 4);
+        std::thread::scope(|scope| {
+            scope.spawn(move || front.for_each(|x| *x *= 10));
+            scope.spawn(move || back.rev().for_each(|x| *x = -*x));
+        });
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![0, 10, 20, -3, -4, -5, -6]
+        );
+
+        for mid in [0, 7] {
+            let (front, back) = list.split_at_mut(mid);
+            assert_eq!((front.count(), back.count()), (mid, 7 - mid));
+        }
+        assert!(panics(|| {
+            generate_test().split_at_mut(8);
+        }));
+    }
+
+    #[test]
+    fn test_partition() {
+        let list = generate_test();
+        let before = live_allocations();
+        let (even, odd) = list.partition(|x| x % 2 == 0);
+        assert_eq!(live_allocations(), before);
+        assert!(even.iter().eq([0, 2, 4, 6].iter()));
+        assert!(odd.iter().rev().eq([5, 3, 1].iter()));
+        assert_eq!((even.len(), odd.len()), (4, 3));
+        assert_eq!(odd.front(), Some(&1));
+        assert_eq!(even.back(), Some(&6));
+
+        let (all, none) = generate_test().partition(|_| true);
+        assert_eq!((all.len(), none.len()), (7, 0));
+
+        // a panicking predicate drops every element once
+        let counter = DropCounter::new();
+        let list: LinkedList<_> = (0..5).map(|id| counter.track(id)).collect();
+        assert!(panics(move || {
+            list.partition(|t| {
+                assert!(t.id != 3);
+                true
+            });
+        }));
+        assert_eq!(counter.count(), 5);
+    }
+
+    #[test]
+    fn test_par_parts() {
+        for len in [0, 1, 1023, 1024, 2047, 2048, 10_000, 16 * 1024 + 5, 100_000] {
+            let list: LinkedList<i32> = (0..len).collect();
+            let parts = list.par_parts();
+            let lens: Vec<usize> = parts.iter().map(|part| part.2).collect();
+            assert_eq!(lens.iter().sum::<usize>(), len as usize);
+            assert!(lens.len() <= super::PAR_MAX_PARTS);
+            assert!(lens.iter().max().unwrap() - lens.iter().min().unwrap() <= 1);
+            // each part starts right after the previous one
+            let starts: Vec<i32> = list
+                .par_iters()
+                .filter_map(|mut i| i.next().copied())
+                .collect();
+            let mut expected = 0;
+            for (start, len) in starts.iter().zip(&lens) {
+                assert_eq!(*start, expected);
+                expected += *len as i32;
+            }
+        }
+    }
+
+    #[test]
+    fn test_par_iteration() {
+        let mut list: LinkedList<u64> = (0..50_000).collect();
+        list.par_for_each(|x| *x = *x * *x);
+        assert!(list.iter().copied().eq((0..50_000).map(|x| x * x)));
+
+        let strings = list.par_map_collect(|x| x.to_string());
+        assert_eq!(strings.len(), 50_000);
+        assert!(strings
+            .iter()
+            .zip(list.iter())
+            .all(|(s, x)| *s == x.to_string()));
+        assert!(strings.iter().rev().eq(list
+            .iter()
+            .rev()
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>()
+            .iter()));
+
+        assert_eq!(list.par_sum::<u64>(), list.iter().sum());
+
+        // floating point sums depend on the grouping, which is always the same
+        let floats: LinkedList<f64> = (1..100_000).map(|x| 1.0 / x as f64).collect();
+        let sum = floats.par_sum::<f64>();
+        for _ in 0..3 {
+            assert_eq!(floats.par_sum::<f64>().to_bits(), sum.to_bits());
+        }
+
+        let mut empty = LinkedList::<i32>::new();
+        empty.par_for_each(|_| unreachable!());
+        assert!(empty.par_map_collect(|x| *x).is_empty());
+        assert_eq!(empty.par_sum::<i32>(), 0);
+    }
+
+    #[test]
+    fn test_par_panic() {
+        let mut list: LinkedList<i32> = (0..10_000).collect();
+        assert!(panics(|| list.par_for_each(|x| assert!(*x != 9_000))));
+        assert!(panics(|| {
+            list.par_map_collect(|x| assert!(*x != 42));
+        }));
+        // the list is still whole
+        assert_eq!(list.len(), 10_000);
     }
 }
@@ -0,0 +1,441 @@
+// "A Skew-Binary Random Access List", a persistent list like "third.rs" that
+// can also be indexed. To learn about:
+//   1) Numerical representations (Okasaki)
+//   2) Path copying
+
+// Takeaways:
+//   1. Every n has a unique "skew binary" form, a sum of numbers 2^k - 1 where
+//      only the smallest one may appear twice. We keep one complete binary tree
+//      per term, so a list of n elements is a short list ("spine") of trees.
+//   2. Adding 1 to a skew binary number touches at most the two smallest terms:
+//      two trees of size s plus one element make a tree of size 2s + 1. So
+//      `cons` is O(1), and `tail` is the same step backwards.
+//   3. Each tree stores its elements in preorder, root first, then the left
+//      subtree, then the right one. `get` skips whole trees along the spine,
+//      then halves the subtree at every step: O(log n).
+//   4. `update` can't touch a node another version may share. It copies the
+//      path from the root to the element and reuses every other subtree, so a
+//      new version costs O(log n) new nodes.
+//   5. The spine has O(log n) digits and a tree is O(log n) deep, unlike the
+//      other lists the default recursive destructor is fine here.
+
+use std::rc::Rc;
+
+pub struct List<T> {
+    spine: Spine<T>,
+    len: usize,
+}
+
+type Spine<T> = Option<Rc<Digit<T>>>;
+
+// one term of the skew binary number, a complete tree of `size` elements
+struct Digit<T> {
+    size: usize,
+    tree: Rc<Tree<T>>,
+    next: Spine<T>,
+}
+
+enum Tree<T> {
+    Leaf(T),
+    Node(T, Rc<Tree<T>>, Rc<Tree<T>>),
+}
+
+impl<T> Tree<T> {
+    fn value(&self) -> &T {
+        match self {
+            Tree::Leaf(elem) | Tree::Node(elem, _, _) => elem,
+        }
+    }
+
+    // `index` must be smaller than `size`, the number of elements in the tree
+    fn get(&self, mut size: usize, mut index: usize) -> &T {
+        let mut tree = self;
+        loop {
+            match tree {
+                Tree::Node(_, left, right) if index > 0 => {
+                    size /= 2;
+                    if index <= size {
+                        tree = left;
+                        index -= 1;
+                    } else {
+                        tree = right;
+                        index -= 1 + size;
+                    }
+                }
+                _ => return tree.value(),
+            }
+        }
+    }
+}
+
+impl<T: Clone> Tree<T> {
+    // copies the path to `index`, the values on it are cloned
+    fn update(tree: &Rc<Tree<T>>, size: usize, index: usize, elem: T) -> Rc<Tree<T>> {
+        let half = size / 2;
+        Rc::new(match &**tree {
+            Tree::Leaf(_) => Tree::Leaf(elem),
+            Tree::Node(_, left, right) if index == 0 => {
+                Tree::Node(elem, Rc::clone(left), Rc::clone(right))
+            }
+            Tree::Node(value, left, right) if index <= half => Tree::Node(
+                value.clone(),
+                Tree::update(left, half, index - 1, elem),
+                Rc::clone(right),
+            ),
+            Tree::Node(value, left, right) => Tree::Node(
+                value.clone(),
+                Rc::clone(left),
+                Tree::update(right, half, index - 1 - half, elem),
+            ),
+        })
+    }
+}
+
+impl<T> List<T> {
+    pub fn new() -> Self {
+        List {
+            spine: None,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn cons(&self, elem: T) -> List<T> {
+        let len = self.len + 1;
+        if let Some(first) = &self.spine {
+            if let Some(second) = &first.next {
+                // the two smallest trees have the same size, merge them
+                if first.size == second.size {
+                    let tree = Tree::Node(elem, Rc::clone(&first.tree), Rc::clone(&second.tree));
+                    let digit = Digit {
+                        size: 2 * first.size + 1,
+                        tree: Rc::new(tree),
+                        next: second.next.clone(),
+                    };
+                    return List {
+                        spine: Some(Rc::new(digit)),
+                        len,
+                    };
+                }
+            }
+        }
+        let digit = Digit {
+            size: 1,
+            tree: Rc::new(Tree::Leaf(elem)),
+            next: self.spine.clone(),
+        };
+        return List {
+            spine: Some(Rc::new(digit)),
+            len,
+        };
+    }
+
+    pub fn head(&self) -> Option<&T> {
+        self.spine.as_ref().map(|digit| digit.tree.value())
+    }
+
+    // the list without its head, splits the first tree back in two
+    pub fn tail(&self) -> List<T> {
+        let first = match &self.spine {
+            Some(first) => first,
+            None => return List::new(),
+        };
+        let spine = match &*first.tree {
+            Tree::Leaf(_) => first.next.clone(),
+            Tree::Node(_, left, right) => {
+                let size = first.size / 2;
+                let right = Digit {
+                    size,
+                    tree: Rc::clone(right),
+                    next: first.next.clone(),
+                };
+                let left = Digit {
+                    size,
+                    tree: Rc::clone(left),
+                    next: Some(Rc::new(right)),
+                };
+                Some(Rc::new(left))
+            }
+        };
+        return List {
+            spine,
+            len: self.len - 1,
+        };
+    }
+
+    pub fn get(&self, mut index: usize) -> Option<&T> {
+        let mut spine = &self.spine;
+        while let Some(digit) = spine {
+            if index < digit.size {
+                return Some(digit.tree.get(digit.size, index));
+            }
+            index -= digit.size;
+            spine = &digit.next;
+        }
+        return None;
+    }
+}
+
+impl<T: Clone> List<T> {
+    // a new version with the element at `index` replaced, None if out of bounds
+    pub fn update(&self, index: usize, elem: T) -> Option<List<T>> {
+        fn update_spine<T: Clone>(spine: &Spine<T>, index: usize, elem: T) -> Spine<T> {
+            let digit = spine.as_ref()?;
+            let digit = if index < digit.size {
+                Digit {
+                    size: digit.size,
+                    tree: Tree::update(&digit.tree, digit.size, index, elem),
+                    next: digit.next.clone(),
+                }
+            } else {
+                Digit {
+                    size: digit.size,
+                    tree: Rc::clone(&digit.tree),
+                    next: Some(update_spine(&digit.next, index - digit.size, elem)?),
+                }
+            };
+            Some(Rc::new(digit))
+        }
+
+        if index >= self.len {
+            return None;
+        }
+        Some(List {
+            spine: update_spine(&self.spine, index, elem),
+            len: self.len,
+        })
+    }
+}
+
+// front to back, each tree in preorder
+pub struct Iter<'a, T> {
+    spine: Option<&'a Digit<T>>,
+    trees: Vec<&'a Tree<T>>,
+    len: usize,
+}
+
+impl<T> List<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            spine: self.spine.as_deref(),
+            trees: Vec::new(),
+            len: self.len,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let tree = match self.trees.pop() {
+            Some(tree) => tree,
+            None => {
+                let digit = self.spine?;
+                self.spine = digit.next.as_deref();
+                &*digit.tree
+            }
+        };
+        if let Tree::Node(_, left, right) = tree {
+            self.trees.push(right);
+            self.trees.push(left);
+        }
+        self.len -= 1;
+        Some(tree.value())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+//////////////////////
+// other interfaces //
+//////////////////////
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// cheap, the clone shares everything
+impl<T> Clone for List<T> {
+    fn clone(&self) -> Self {
+        List {
+            spine: self.spine.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// keeps the order, the first item becomes the head
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let items: Vec<T> = iter.into_iter().collect();
+        items
+            .into_iter()
+            .rev()
+            .fold(Self::new(), |list, elem| list.cons(elem))
+    }
+}
+
+impl<T: PartialEq> PartialEq for List<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+impl<T: Eq> Eq for List<T> {}
+
+#[cfg(test)]
+mod test {
+    use super::List;
+    use crate::test_helpers::DropCounter;
+
+    fn values(list: &List<i32>) -> Vec<i32> {
+        list.iter().copied().collect()
+    }
+
+    // sizes of the trees along the spine
+    fn digits<T>(list: &List<T>) -> Vec<usize> {
+        let mut sizes = Vec::new();
+        let mut spine = &list.spine;
+        while let Some(digit) = spine {
+            sizes.push(digit.size);
+            spine = &digit.next;
+        }
+        sizes
+    }
+
+    #[test]
+    fn basics() {
+        let list = List::new();
+        assert_eq!(list.head(), None);
+        assert!(list.tail().is_empty());
+
+        let list = list.cons(1).cons(2).cons(3);
+        assert_eq!(list.head(), Some(&3));
+        assert_eq!(list.len(), 3);
+
+        let list = list.tail();
+        assert_eq!(list.head(), Some(&2));
+        let list = list.tail();
+        assert_eq!(list.head(), Some(&1));
+        let list = list.tail();
+        assert_eq!(list.head(), None);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn skew_binary_digits() {
+        let mut list = List::new();
+        for i in 0..7 {
+            list = list.cons(i);
+        }
+        // 7 = 3 + 3 + 1 would break the "only the smallest repeats" rule
+        assert_eq!(digits(&list), vec![7]);
+        assert_eq!(digits(&list.cons(7)), vec![1, 7]);
+        assert_eq!(digits(&list.tail()), vec![3, 3]);
+
+        // sizes are 2^k - 1, increasing, only the first one may repeat
+        let list: List<usize> = (0..1000).collect();
+        let sizes = digits(&list);
+        assert_eq!(sizes.iter().sum::<usize>(), 1000);
+        assert!(sizes.iter().all(|size| (size + 1).is_power_of_two()));
+        assert!(sizes.windows(2).skip(1).all(|pair| pair[0] < pair[1]));
+        assert!(sizes.len() <= 2 * 10);
+    }
+
+    #[test]
+    fn get_and_iter() {
+        for len in 0..70 {
+            let list: List<i32> = (0..len).collect();
+            assert_eq!(values(&list), (0..len).collect::<Vec<_>>());
+            assert_eq!(list.iter().len(), len as usize);
+            for i in 0..len {
+                assert_eq!(list.get(i as usize), Some(&i));
+            }
+            assert_eq!(list.get(len as usize), None);
+        }
+    }
+
+    #[test]
+    fn update() {
+        let list: List<i32> = (0..100).collect();
+        let mut versions = vec![list.clone()];
+        let mut model: Vec<i32> = (0..100).collect();
+        let mut current = list;
+        for i in (0..100).step_by(7) {
+            current = current.update(i, -(i as i32)).unwrap();
+            model[i] = -(i as i32);
+            assert_eq!(values(&current), model);
+            versions.push(current.clone());
+        }
+        assert!(current.update(100, 0).is_none());
+        // every version still reads the same
+        assert_eq!(values(&versions[0]), (0..100).collect::<Vec<_>>());
+        assert_eq!(versions[1].get(0), Some(&0));
+        assert_eq!(versions[2].get(7), Some(&-7));
+        assert_eq!(versions[1].get(7), Some(&7));
+    }
+
+    #[test]
+    fn shares_structure() {
+        let list: List<i32> = (0..15).collect();
+        let tail = list.tail();
+        let updated = list.update(14, 0).unwrap();
+        // the tail is made of the subtrees of the list's single tree
+        assert!(std::ptr::eq(list.get(1).unwrap(), tail.get(0).unwrap()));
+        assert!(std::ptr::eq(list.get(8).unwrap(), tail.get(7).unwrap()));
+        // updating the last element copies its path, the left subtree is shared
+        assert!(std::ptr::eq(list.get(1).unwrap(), updated.get(1).unwrap()));
+        assert!(!std::ptr::eq(list.get(8).unwrap(), updated.get(8).unwrap()));
+    }
+
+    #[test]
+    fn traits() {
+        let list: List<i32> = (1..=3).collect();
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+        assert_eq!(list, List::default().cons(3).cons(2).cons(1));
+        assert_eq!(list, list.clone());
+        assert_ne!(list, list.tail());
+    }
+
+    #[test]
+    fn drop_shared() {
+        let counter = DropCounter::new();
+        let mut list = List::new();
+        for id in 0..7 {
+            list = list.cons(counter.track(id));
+        }
+        let tail = list.tail();
+
+        // only the head belonged to `list` alone
+        drop(list);
+        assert_eq!(counter.dropped(), vec![6]);
+        drop(tail);
+        assert_eq!(counter.count(), 7);
+    }
+
+    #[test]
+    fn drop_long_list() {
+        let list: List<i32> = (0..1_000_000).collect();
+        let tail = list.tail();
+        drop(list);
+        drop(tail);
+    }
+}
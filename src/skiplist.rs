@@ -0,0 +1,821 @@
+// "A Skip List Map", an ordered map built with the node/pointer discipline of
+// "sixth.rs". To learn about:
+//   1) Probabilistic balancing
+//   2) Nodes with a variable number of links
+
+// Takeaways:
+//   1. Every node has a "tower" of forward links, level 0 links all nodes
+//      in order like a singly-linked list, and each level above skips over
+//      roughly half of the nodes of the level below.
+//   2. The height of a tower is drawn at random (P(height > h) = 1/2^h), so
+//      searching from the top level down takes O(log n) steps on average,
+//      without any rebalancing. The generator is a tiny deterministic
+//      xorshift, so the same inserts always build the same towers.
+//   3. Search returns the last node before the key on every level, that's
+//      exactly the set of links we need to rewrite to insert or remove.
+//   4. As in "sixth.rs", links are Option<NonNull<_>> so the map is covariant
+//      over K and V, and PhantomData says we own K and V by-value.
+//   5. The head of each level lives in the map itself, a `None` predecessor
+//      means "the head".
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+use std::ptr::NonNull;
+
+const MAX_HEIGHT: usize = 32;
+const DEFAULT_SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+pub struct SkipList<K, V> {
+    // first node of every level
+    head: [Link<K, V>; MAX_HEIGHT],
+    // last node on level 0, for `last` and reverse iteration
+    back: Link<K, V>,
+    // number of levels in use
+    height: usize,
+    len: usize,
+    rng: Rng,
+    // We semantically store values of K and V by-value.
+    _boo: PhantomData<(K, V)>,
+}
+
+type Link<K, V> = Option<NonNull<Node<K, V>>>;
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    // previous node on level 0
+    prev: Link<K, V>,
+    // next node on every level of the tower
+    tower: Box<[Link<K, V>]>,
+}
+
+// xorshift64*, small and good enough to draw tower heights
+#[derive(Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+        x.wrapping_mul(DEFAULT_SEED)
+    }
+
+    // geometric distribution, every level is half as likely as the one below
+    fn height(&mut self) -> usize {
+        (self.next().trailing_zeros() as usize + 1).min(MAX_HEIGHT)
+    }
+}
+
+impl<K, V> SkipList<K, V> {
+    pub fn new() -> Self {
+        Self::with_seed(DEFAULT_SEED)
+    }
+
+    // Two maps with the same seed and the same inserts have the same shape.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            head: [None; MAX_HEIGHT],
+            back: None,
+            height: 0,
+            len: 0,
+            // xorshift gets stuck at 0
+            rng: Rng(if seed == 0 { DEFAULT_SEED } else { seed }),
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.head[0].map(|node| unsafe { Node::entry(node) })
+    }
+
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.back.map(|node| unsafe { Node::entry(node) })
+    }
+
+    // The first node is directly linked from the head on all of its levels,
+    // so removing it doesn't need a search.
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        self.head[0].map(|node| unsafe {
+            let boxed_node = Box::from_raw(node.as_ptr());
+            for (level, next) in boxed_node.tower.iter().enumerate() {
+                self.head[level] = *next;
+            }
+            match boxed_node.tower[0] {
+                Some(next) => (*next.as_ptr()).prev = None,
+                None => self.back = None,
+            }
+            self.trim_height();
+            self.len -= 1;
+            (boxed_node.key, boxed_node.value)
+        })
+    }
+
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        // the predecessors of the last node are only known through a search,
+        // walking right until the next node is `last` needs no comparison
+        let last = self.back?;
+        let key: *const K = unsafe { &(*last.as_ptr()).key };
+        let update = self.search(|k| !std::ptr::eq(k, key));
+
+        unsafe {
+            let boxed_node = Box::from_raw(last.as_ptr());
+            for (level, prev) in update.iter().enumerate().take(boxed_node.tower.len()) {
+                self.set_next(*prev, level, None);
+            }
+            self.back = boxed_node.prev;
+            self.trim_height();
+
+            self.len -= 1;
+            Some((boxed_node.key, boxed_node.value))
+        }
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_first().is_some() {}
+    }
+
+    // drops the empty levels at the top after a removal
+    fn trim_height(&mut self) {
+        while self.height > 0 && self.head[self.height - 1].is_none() {
+            self.height -= 1;
+        }
+    }
+
+    // next node after `prev` on `level`, `None` as `prev` is the head
+    unsafe fn next(&self, prev: Link<K, V>, level: usize) -> Link<K, V> {
+        match prev {
+            Some(node) => (*node.as_ptr()).tower[level],
+            None => self.head[level],
+        }
+    }
+
+    unsafe fn set_next(&mut self, prev: Link<K, V>, level: usize, next: Link<K, V>) {
+        match prev {
+            Some(node) => (&mut (*node.as_ptr()).tower)[level] = next,
+            None => self.head[level] = next,
+        }
+    }
+
+    // Walks down from the top level, moving right while `advance` holds for
+    // the next key. Returns the last node visited on every level.
+    fn search<F: Fn(&K) -> bool>(&self, advance: F) -> [Link<K, V>; MAX_HEIGHT] {
+        let mut update = [None; MAX_HEIGHT];
+        let mut cur = None;
+        for level in (0..self.height).rev() {
+            unsafe {
+                while let Some(next) = self.next(cur, level) {
+                    if !advance(&(*next.as_ptr()).key) {
+                        break;
+                    }
+                    cur = Some(next);
+                }
+            }
+            update[level] = cur;
+        }
+        update
+    }
+}
+
+impl<K, V> Node<K, V> {
+    unsafe fn entry<'a>(node: NonNull<Self>) -> (&'a K, &'a V) {
+        let node = &*node.as_ptr();
+        (&node.key, &node.value)
+    }
+}
+
+impl<K: Ord, V> SkipList<K, V> {
+    // Inserts a key-value pair, returns the old value if the key was present.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let update = self.search(|k| k < &key);
+
+        unsafe {
+            if let Some(found) = self.next(update[0], 0) {
+                if (*found.as_ptr()).key == key {
+                    return Some(std::mem::replace(&mut (*found.as_ptr()).value, value));
+                }
+            }
+
+            let height = self.rng.height();
+            let new_node = NonNull::new_unchecked(Box::into_raw(Box::new(Node {
+                key,
+                value,
+                prev: update[0],
+                tower: vec![None; height].into_boxed_slice(),
+            })));
+
+            // levels above the current height start from the head,
+            // and `update` is already None there
+            for (level, prev) in update.iter().enumerate().take(height) {
+                let next = self.next(*prev, level);
+                (&mut (*new_node.as_ptr()).tower)[level] = next;
+                self.set_next(*prev, level, Some(new_node));
+            }
+            match (*new_node.as_ptr()).tower[0] {
+                Some(next) => (*next.as_ptr()).prev = Some(new_node),
+                None => self.back = Some(new_node),
+            }
+
+            self.height = self.height.max(height);
+        }
+        self.len += 1;
+        None
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let update = self.search(|k| k.borrow() < key);
+
+        unsafe {
+            let node = self.next(update[0], 0)?;
+            if (*node.as_ptr()).key.borrow() != key {
+                return None;
+            }
+
+            // unlink on every level of its tower, then from level 0's prev
+            let boxed_node = Box::from_raw(node.as_ptr());
+            for (level, next) in boxed_node.tower.iter().enumerate() {
+                self.set_next(update[level], level, *next);
+            }
+            match boxed_node.tower[0] {
+                Some(next) => (*next.as_ptr()).prev = boxed_node.prev,
+                None => self.back = boxed_node.prev,
+            }
+            self.trim_height();
+
+            self.len -= 1;
+            Some((boxed_node.key, boxed_node.value))
+        }
+    }
+
+    fn find<Q>(&self, key: &Q) -> Link<K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let update = self.search(|k| k.borrow() < key);
+        unsafe {
+            self.next(update[0], 0)
+                .filter(|node| (*node.as_ptr()).key.borrow() == key)
+        }
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key)
+            .map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key)
+            .map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    // Iterates over the entries whose keys are within `range`, in order.
+    //
+    // Panics like BTreeMap::range if the start is after the end, or if both
+    // are equal and excluded.
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        match (range.start_bound(), range.end_bound()) {
+            (Bound::Excluded(start), Bound::Excluded(end)) if start == end => {
+                panic!("range start and end are equal and excluded in SkipList")
+            }
+            (
+                Bound::Included(start) | Bound::Excluded(start),
+                Bound::Included(end) | Bound::Excluded(end),
+            ) if start > end => panic!("range start is greater than range end in SkipList"),
+            _ => {}
+        }
+
+        // first node inside the start bound
+        let front = match range.start_bound() {
+            Bound::Included(start) => unsafe {
+                self.next(self.search(|k| k.borrow() < start)[0], 0)
+            },
+            Bound::Excluded(start) => unsafe {
+                self.next(self.search(|k| k.borrow() <= start)[0], 0)
+            },
+            Bound::Unbounded => self.head[0],
+        };
+        // last node inside the end bound
+        let back = match range.end_bound() {
+            Bound::Included(end) => self.search(|k| k.borrow() <= end)[0],
+            Bound::Excluded(end) => self.search(|k| k.borrow() < end)[0],
+            Bound::Unbounded => self.back,
+        };
+
+        // the bounds cross when no key falls inside them
+        let empty = match (front, back) {
+            (Some(front), Some(back)) => unsafe {
+                (*front.as_ptr()).key.cmp(&(*back.as_ptr()).key) == Ordering::Greater
+            },
+            _ => true,
+        };
+
+        if empty {
+            Range {
+                front: None,
+                back: None,
+                _boo: PhantomData,
+            }
+        } else {
+            Range {
+                front,
+                back,
+                _boo: PhantomData,
+            }
+        }
+    }
+}
+
+impl<K, V> Drop for SkipList<K, V> {
+    fn drop(&mut self) {
+        // keep popping the rest if dropping an entry panics, see "sixth.rs"
+        struct DropGuard<'a, K, V>(&'a mut SkipList<K, V>);
+        impl<'a, K, V> Drop for DropGuard<'a, K, V> {
+            fn drop(&mut self) {
+                while self.0.pop_first().is_some() {}
+            }
+        }
+
+        while let Some(entry) = self.pop_first() {
+            let guard = DropGuard(self);
+            drop(entry);
+            std::mem::forget(guard);
+        }
+    }
+}
+
+// iterator over all entries
+pub struct Iter<'a, K, V> {
+    front: Link<K, V>,
+    back: Link<K, V>,
+    len: usize,
+    _boo: PhantomData<&'a (K, V)>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        self.front.map(|node| unsafe {
+            self.front = (*node.as_ptr()).tower[0];
+            Node::entry(node)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        self.back.map(|node| unsafe {
+            self.back = (*node.as_ptr()).prev;
+            Node::entry(node)
+        })
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+impl<K, V> SkipList<K, V> {
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            front: self.head[0],
+            back: self.back,
+            len: self.len,
+            _boo: PhantomData,
+        }
+    }
+}
+
+// iterator over a range of entries, both ends are inclusive and it is
+// finished once they meet
+pub struct Range<'a, K, V> {
+    front: Link<K, V>,
+    back: Link<K, V>,
+    _boo: PhantomData<&'a (K, V)>,
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.front.map(|node| unsafe {
+            if self.front == self.back {
+                self.front = None;
+                self.back = None;
+            } else {
+                self.front = (*node.as_ptr()).tower[0];
+            }
+            Node::entry(node)
+        })
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Range<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.back.map(|node| unsafe {
+            if self.front == self.back {
+                self.front = None;
+                self.back = None;
+            } else {
+                self.back = (*node.as_ptr()).prev;
+            }
+            Node::entry(node)
+        })
+    }
+}
+
+// into iterator
+pub struct IntoIter<K, V>(SkipList<K, V>);
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_first()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.pop_last()
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+impl<K, V> SkipList<K, V> {
+    pub fn into_iter(self) -> IntoIter<K, V> {
+        IntoIter(self)
+    }
+}
+
+//////////////////////
+// other interfaces //
+//////////////////////
+
+impl<K, V> Default for SkipList<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: std::fmt::Debug, V: std::fmt::Debug> std::fmt::Debug for SkipList<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Ord + Clone, V: Clone> Clone for SkipList<K, V> {
+    fn clone(&self) -> Self {
+        // continue from our generator state, so cloning stays deterministic
+        let mut map = Self::with_seed(self.rng.0);
+        map.extend(self.iter().map(|(k, v)| (k.clone(), v.clone())));
+        map
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for SkipList<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for SkipList<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+// equal if they hold the same entries, the shape of the towers doesn't matter
+impl<K: PartialEq, V: PartialEq> PartialEq for SkipList<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+impl<K: Eq, V: Eq> Eq for SkipList<K, V> {}
+
+unsafe impl<K: Send, V: Send> Send for SkipList<K, V> {}
+unsafe impl<K: Sync, V: Sync> Sync for SkipList<K, V> {}
+
+unsafe impl<'a, K: Sync, V: Sync> Send for Iter<'a, K, V> {}
+unsafe impl<'a, K: Sync, V: Sync> Sync for Iter<'a, K, V> {}
+
+unsafe impl<'a, K: Sync, V: Sync> Send for Range<'a, K, V> {}
+unsafe impl<'a, K: Sync, V: Sync> Sync for Range<'a, K, V> {}
+
+// test Sync and Send traits
+#[allow(dead_code)]
+fn assert_properties() {
+    fn is_send<T: Send>() {}
+    fn is_sync<T: Sync>() {}
+
+    is_send::<SkipList<i32, i32>>();
+    is_sync::<SkipList<i32, i32>>();
+
+    is_send::<IntoIter<i32, i32>>();
+    is_sync::<IntoIter<i32, i32>>();
+
+    is_send::<Iter<i32, i32>>();
+    is_sync::<Iter<i32, i32>>();
+
+    is_send::<Range<i32, i32>>();
+    is_sync::<Range<i32, i32>>();
+
+    fn skip_list_covariant<'a, T>(x: SkipList<&'static T, &'static T>) -> SkipList<&'a T, &'a T> {
+        x
+    }
+    fn iter_covariant<'i, 'a, T>(x: Iter<'i, &'static T, &'static T>) -> Iter<'i, &'a T, &'a T> {
+        x
+    }
+    fn range_covariant<'i, 'a, T>(x: Range<'i, &'static T, &'static T>) -> Range<'i, &'a T, &'a T> {
+        x
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SkipList;
+    use crate::test_helpers::{panics, DropCounter};
+    use std::collections::BTreeMap;
+    use std::ops::Bound::{Excluded, Included, Unbounded};
+
+    // checks the links of every level and returns the tower heights
+    fn heights<K: Ord, V>(map: &SkipList<K, V>) -> Vec<usize> {
+        let mut heights = Vec::new();
+        unsafe {
+            let mut prev = None;
+            let mut cur = map.head[0];
+            while let Some(node) = cur {
+                assert_eq!((*node.as_ptr()).prev, prev);
+                if let Some(prev) = prev {
+                    assert!((*prev.as_ptr()).key < (*node.as_ptr()).key);
+                }
+                let tower: &[_] = &(*node.as_ptr()).tower;
+                heights.push(tower.len());
+                prev = cur;
+                cur = (*node.as_ptr()).tower[0];
+            }
+            assert_eq!(map.back, prev);
+
+            // each level is a sorted sub-list of the level below
+            for level in 1..super::MAX_HEIGHT {
+                let mut below = map.head[level - 1];
+                let mut cur = map.head[level];
+                while let Some(node) = cur {
+                    while below != Some(node) {
+                        below = (*below.unwrap().as_ptr()).tower[level - 1];
+                    }
+                    cur = (*node.as_ptr()).tower[level];
+                }
+            }
+        }
+        assert_eq!(heights.len(), map.len());
+        assert_eq!(heights.iter().copied().max().unwrap_or(0), map.height);
+        heights
+    }
+
+    #[test]
+    fn basics() {
+        let mut map = SkipList::new();
+        assert!(map.is_empty());
+        assert_eq!(map.get(&1), None);
+        assert_eq!(map.remove(&1), None);
+        assert_eq!(map.first(), None);
+        assert_eq!(map.last(), None);
+
+        assert_eq!(map.insert(3, "c"), None);
+        assert_eq!(map.insert(1, "a"), None);
+        assert_eq!(map.insert(2, "b"), None);
+        assert_eq!(map.insert(2, "B"), Some("b"));
+        assert_eq!(map.len(), 3);
+        heights(&map);
+
+        assert_eq!(map.get(&2), Some(&"B"));
+        assert!(map.contains_key(&3));
+        assert!(!map.contains_key(&4));
+        assert_eq!(map.first(), Some((&1, &"a")));
+        assert_eq!(map.last(), Some((&3, &"c")));
+
+        *map.get_mut(&1).unwrap() = "A";
+        assert_eq!(map.remove(&3), Some("c"));
+        assert_eq!(map.remove(&3), None);
+        assert_eq!(map.last(), Some((&2, &"B")));
+        heights(&map);
+
+        assert_eq!(map.pop_first(), Some((1, "A")));
+        assert_eq!(map.pop_last(), Some((2, "B")));
+        assert_eq!(map.pop_last(), None);
+        assert!(map.is_empty());
+        assert_eq!(map.height, 0);
+
+        // the exhaustion case fixed the pointers right
+        map.insert(5, "e");
+        assert_eq!(map.first(), map.last());
+    }
+
+    #[test]
+    fn borrowed_keys() {
+        let mut map = SkipList::new();
+        map.insert(String::from("b"), 2);
+        map.insert(String::from("a"), 1);
+        assert_eq!(map.get("a"), Some(&1));
+        assert_eq!(map.range::<str, _>((Included("b"), Unbounded)).count(), 1);
+        assert_eq!(map.remove("b"), Some(2));
+    }
+
+    #[test]
+    fn iter() {
+        let map: SkipList<_, _> = [5, 1, 4, 2, 3].iter().map(|&k| (k, k * 10)).collect();
+
+        let mut iter = map.iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next(), Some((&1, &10)));
+        assert_eq!(iter.next_back(), Some((&5, &50)));
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.map(|(k, _)| *k).collect::<Vec<_>>(), vec![2, 3, 4]);
+
+        let mut iter = map.clone().into_iter();
+        assert_eq!(iter.next_back(), Some((5, 50)));
+        assert_eq!(iter.next(), Some((1, 10)));
+        assert_eq!(iter.collect::<Vec<_>>(), vec![(2, 20), (3, 30), (4, 40)]);
+    }
+
+    #[test]
+    fn range() {
+        let map: SkipList<_, _> = (0..20).map(|k| (k * 2, ())).collect();
+        let keys = |r: super::Range<'_, i32, ()>| r.map(|(k, _)| *k).collect::<Vec<_>>();
+
+        assert_eq!(keys(map.range(4..10)), vec![4, 6, 8]);
+        assert_eq!(keys(map.range(3..=10)), vec![4, 6, 8, 10]);
+        assert_eq!(keys(map.range((Excluded(4), Included(8)))), vec![6, 8]);
+        assert_eq!(keys(map.range(..3)), vec![0, 2]);
+        assert_eq!(keys(map.range(35..)), vec![36, 38]);
+        assert_eq!(keys(map.range((Unbounded, Excluded(0)))), vec![]);
+        assert_eq!(keys(map.range(5..6)), vec![]);
+        assert_eq!(keys(map.range(100..)), vec![]);
+        assert_eq!(map.range(..).count(), 20);
+
+        let mut range = map.range(10..=16);
+        assert_eq!(range.next_back(), Some((&16, &())));
+        assert_eq!(range.next(), Some((&10, &())));
+        assert_eq!(range.next_back(), Some((&14, &())));
+        assert_eq!(range.next(), Some((&12, &())));
+        assert_eq!(range.next(), None);
+        assert_eq!(range.next_back(), None);
+
+        assert!(map
+            .range(0..40)
+            .rev()
+            .map(|(k, _)| *k)
+            .eq((0..20).rev().map(|k| k * 2)));
+
+        // the same bounds BTreeMap::range rejects
+        assert_eq!(keys(map.range(4..4)), vec![]);
+        assert_eq!(keys(map.range(4..=4)), vec![4]);
+        assert!(panics(|| {
+            map.range((Included(6), Excluded(4)));
+        }));
+        assert!(panics(|| {
+            map.range((Included(5), Included(4)));
+        }));
+        assert!(panics(|| {
+            map.range((Excluded(4), Excluded(4)));
+        }));
+        assert_eq!(keys(map.range((Excluded(4), Included(4)))), vec![]);
+    }
+
+    #[test]
+    fn against_btree_map() {
+        // a long random workload compared step by step with std's BTreeMap
+        let mut map = SkipList::with_seed(7);
+        let mut model = BTreeMap::new();
+        let mut rng = super::Rng(42);
+
+        for step in 0..5000 {
+            let key = rng.next() % 500;
+            if rng.next().is_multiple_of(3) {
+                assert_eq!(map.remove(&key), model.remove(&key));
+            } else {
+                assert_eq!(map.insert(key, step), model.insert(key, step));
+            }
+            assert_eq!(map.len(), model.len());
+        }
+
+        heights(&map);
+        assert!(map.iter().eq(model.iter()));
+        assert!(map.range(100..200).eq(model.range(100..200)));
+        assert!(map.range(..=250).rev().eq(model.range(..=250).rev()));
+        assert_eq!(map.first(), model.first_key_value());
+        assert_eq!(map.last(), model.last_key_value());
+    }
+
+    #[test]
+    fn deterministic() {
+        let a: SkipList<_, _> = (0..100).map(|k| (k, ())).collect();
+        let b: SkipList<_, _> = (0..100).map(|k| (k, ())).collect();
+        assert_eq!(heights(&a), heights(&b));
+
+        let c: SkipList<_, _> = {
+            let mut c = SkipList::with_seed(1);
+            c.extend((0..100).map(|k| (k, ())));
+            c
+        };
+        assert_ne!(heights(&a), heights(&c));
+        // same entries, different towers
+        assert_eq!(a, c);
+    }
+
+    #[test]
+    fn traits() {
+        let map: SkipList<_, _> = [(2, 'b'), (1, 'a')].into_iter().collect();
+        assert_eq!(format!("{:?}", map), "{1: 'a', 2: 'b'}");
+
+        let mut other = map.clone();
+        assert_eq!(map, other);
+        other.insert(3, 'c');
+        assert_ne!(map, other);
+        other.remove(&3);
+        other.insert(2, 'B');
+        assert_ne!(map, other);
+    }
+
+    #[test]
+    fn drop_count() {
+        let counter = DropCounter::new();
+        let mut map = SkipList::new();
+        for id in 0..10 {
+            map.insert(id, counter.track(id));
+        }
+        // replacing drops the old value only
+        map.insert(3, counter.track(30));
+        assert_eq!(counter.dropped(), vec![3]);
+        drop(map.remove(&5));
+        assert_eq!(counter.dropped(), vec![3, 5]);
+
+        drop(map);
+        assert_eq!(counter.dropped(), vec![3, 5, 0, 1, 2, 30, 4, 6, 7, 8, 9]);
+    }
+}
@@ -0,0 +1,603 @@
+// "A Thread-Safe Deque", the deque of "fourth.rs" with Arc<Mutex<T>> in
+// place of Rc<RefCell<T>>. To learn about:
+//   1) Fine-grained locking
+//   2) Lock ordering and why it rules out deadlocks
+//   3) Send and Sync without unsafe
+
+// Takeaways:
+//   1. Every node sits behind its own Mutex, so threads working at opposite ends
+//      of the list don't contend on a single lock. All operations take &self,
+//      a List can be shared between threads as it is.
+//   2. Two sentinel nodes, `head` and `tail`, are always linked. Every element then
+//      has a neighbor on both sides and no operation needs to special-case the
+//      empty list, which is good because "is it empty?" can't be answered
+//      without taking a lock.
+//   3. Locks are always taken front to back, and a node stays locked until its
+//      successor is locked (hand-over-hand). Nodes never move relative to each
+//      other, so that is a fixed order and no two threads can wait on each other.
+//   4. At the back we first need to find the node before `tail`, which is against
+//      that order. We peek at it, let go, lock front to back, and check nothing
+//      changed in between. If it did, we start over.
+//   5. Like "fourth.rs" the back links are Weak, the nodes don't form Arc cycles.
+//   6. Elements are only dropped once the locks are released, so a panicking
+//      destructor can't poison a node.
+
+use std::iter::FusedIterator;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
+
+type NodeRef<T> = Arc<Mutex<Node<T>>>;
+type Link<T> = Option<NodeRef<T>>;
+type WeakLink<T> = Option<Weak<Mutex<Node<T>>>>;
+
+pub struct List<T> {
+    head: NodeRef<T>,
+    tail: NodeRef<T>,
+    // only exact when no operation is running
+    len: AtomicUsize,
+}
+
+struct Node<T> {
+    // None for the sentinels
+    elem: Option<T>,
+    next: Link<T>,
+    prev: WeakLink<T>,
+}
+
+impl<T> Node<T> {
+    fn new(elem: Option<T>, prev: &NodeRef<T>, next: &NodeRef<T>) -> NodeRef<T> {
+        Arc::new(Mutex::new(Node {
+            elem,
+            next: Some(Arc::clone(next)),
+            prev: Some(Arc::downgrade(prev)),
+        }))
+    }
+}
+
+// The links are consistent whenever a lock is released, the only code that
+// can panic under a lock is the caller's (a closure given to `for_each`,
+// `T::clone`), and it can't modify the list. So a poisoned lock is safe to use.
+fn lock<T>(node: &Mutex<Node<T>>) -> MutexGuard<'_, Node<T>> {
+    node.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn is<T>(link: &Link<T>, node: &NodeRef<T>) -> bool {
+    link.as_ref().is_some_and(|link| Arc::ptr_eq(link, node))
+}
+
+impl<T> List<T> {
+    pub fn new() -> Self {
+        let tail = Arc::new(Mutex::new(Node {
+            elem: None,
+            next: None,
+            prev: None,
+        }));
+        let head = Arc::new(Mutex::new(Node {
+            elem: None,
+            next: Some(Arc::clone(&tail)),
+            prev: None,
+        }));
+        lock(&tail).prev = Some(Arc::downgrade(&head));
+        List {
+            head,
+            tail,
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn push_front(&self, elem: T) {
+        let mut head = lock(&self.head);
+        let first = head.next.clone().unwrap();
+        let mut first_guard = lock(&first);
+
+        let new = Node::new(Some(elem), &self.head, &first);
+        first_guard.prev = Some(Arc::downgrade(&new));
+        head.next = Some(new);
+        self.len.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn push_back(&self, elem: T) {
+        loop {
+            let last = self.before_tail();
+            let mut last_guard = lock(&last);
+            let mut tail = lock(&self.tail);
+            // `last` may have been popped before we locked it
+            if !is(&last_guard.next, &self.tail) {
+                continue;
+            }
+
+            let new = Node::new(Some(elem), &last, &self.tail);
+            tail.prev = Some(Arc::downgrade(&new));
+            last_guard.next = Some(new);
+            self.len.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+    }
+
+    pub fn pop_front(&self) -> Option<T> {
+        let mut head = lock(&self.head);
+        let first = head.next.clone().unwrap();
+        if Arc::ptr_eq(&first, &self.tail) {
+            return None;
+        }
+        let mut first_guard = lock(&first);
+        let second = first_guard.next.take().unwrap();
+        let mut second_guard = lock(&second);
+
+        second_guard.prev = Some(Arc::downgrade(&self.head));
+        head.next = Some(Arc::clone(&second));
+        first_guard.prev = None;
+        self.len.fetch_sub(1, Ordering::Relaxed);
+        return first_guard.elem.take();
+    }
+
+    pub fn pop_back(&self) -> Option<T> {
+        loop {
+            let last = self.before_tail();
+            if Arc::ptr_eq(&last, &self.head) {
+                return None;
+            }
+            let prev = match lock(&last).prev.as_ref().and_then(Weak::upgrade) {
+                Some(prev) => prev,
+                // popped from the front meanwhile
+                None => continue,
+            };
+            let mut prev_guard = lock(&prev);
+            let mut last_guard = lock(&last);
+            let mut tail = lock(&self.tail);
+            // something was pushed or popped in between
+            if !is(&prev_guard.next, &last) || !is(&last_guard.next, &self.tail) {
+                continue;
+            }
+
+            tail.prev = Some(Arc::downgrade(&prev));
+            prev_guard.next = last_guard.next.take();
+            last_guard.prev = None;
+            self.len.fetch_sub(1, Ordering::Relaxed);
+            return last_guard.elem.take();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Like `pop_front`, holds the head until the first node is locked, or it
+    // could be popped in between.
+    pub fn front(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        let head = lock(&self.head);
+        let first = head.next.clone().unwrap();
+        let first_guard = lock(&first);
+        first_guard.elem.clone()
+    }
+
+    // Like `pop_back`, checks the last node is still last once it is locked.
+    pub fn back(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        loop {
+            let last = self.before_tail();
+            let last_guard = lock(&last);
+            let _tail = lock(&self.tail);
+            if !is(&last_guard.next, &self.tail) {
+                continue;
+            }
+            return last_guard.elem.clone();
+        }
+    }
+
+    // Calls `f` on every element, front to back. The front end is locked for
+    // the whole walk, the back end keeps working: elements popped from the
+    // back before the walk reaches them are skipped, elements pushed to the
+    // back may or may not be seen. `f` must not use the list, it would deadlock.
+    pub fn for_each<F: FnMut(&T)>(&self, mut f: F) {
+        let head = lock(&self.head);
+        let mut cur = head.next.clone();
+        while let Some(node) = cur {
+            let node = lock(&node);
+            if let Some(elem) = &node.elem {
+                f(elem);
+            }
+            // None if the node was popped from the back meanwhile, and so was
+            // everything after it
+            cur = node.next.clone();
+        }
+    }
+
+    // The node right before the tail sentinel, the head sentinel if empty.
+    // It's alive, it is linked from a node we can't unlink without locking
+    // the tail first.
+    fn before_tail(&self) -> NodeRef<T> {
+        lock(&self.tail)
+            .prev
+            .as_ref()
+            .and_then(Weak::upgrade)
+            .unwrap()
+    }
+}
+
+impl<T> List<T> {
+    // Takes the node after `head` out, `tail` included, and links `head` to
+    // its successor. The node is returned to be dropped without any lock held.
+    fn unlink_first(&mut self) -> Link<T> {
+        let mut head = lock(&self.head);
+        let node = head.next.take()?;
+        let mut locked = lock(&node);
+        locked.prev = None;
+        head.next = locked.next.take();
+        drop(locked);
+        Some(node)
+    }
+}
+
+impl<T> Drop for List<T> {
+    fn drop(&mut self) {
+        // unlink the nodes one by one so dropping a long list doesn't recurse,
+        // and keep doing so from the guard if an element panics
+        struct DropGuard<'a, T>(&'a mut List<T>);
+
+        impl<'a, T> Drop for DropGuard<'a, T> {
+            fn drop(&mut self) {
+                while self.0.unlink_first().is_some() {}
+            }
+        }
+
+        while let Some(node) = self.unlink_first() {
+            let guard = DropGuard(self);
+            drop(node);
+            std::mem::forget(guard);
+        }
+    }
+}
+
+// iterator that takes ownship, IntoIter
+pub struct IntoIter<T>(List<T>);
+
+impl<T> List<T> {
+    pub fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.0.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+impl<T> FusedIterator for IntoIter<T> {}
+
+//////////////////////
+// other interfaces //
+//////////////////////
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut list = f.debug_list();
+        self.for_each(|elem| {
+            list.entry(elem);
+        });
+        list.finish()
+    }
+}
+
+impl<T: Clone> Clone for List<T> {
+    fn clone(&self) -> Self {
+        let list = Self::new();
+        self.for_each(|elem| list.push_back(elem.clone()));
+        list
+    }
+}
+
+impl<T> Extend<T> for List<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push_back(item);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+// Send and Sync come for free: Arc<Mutex<T>> is both as long as T: Send
+#[allow(dead_code)]
+fn assert_properties() {
+    fn is_send<T: Send>() {}
+    fn is_sync<T: Sync>() {}
+
+    is_send::<List<i32>>();
+    is_sync::<List<i32>>();
+    is_send::<IntoIter<i32>>();
+    is_sync::<IntoIter<i32>>();
+}
+
+#[cfg(test)]
+mod test {
+    use super::List;
+    use crate::test_helpers::{live_allocations, panics, DropCounter};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use std::thread;
+
+    fn values(list: &List<i32>) -> Vec<i32> {
+        let mut values = Vec::new();
+        list.for_each(|&elem| values.push(elem));
+        values
+    }
+
+    #[test]
+    fn basics() {
+        let list = List::new();
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.pop_back(), None);
+
+        list.push_front(2);
+        list.push_front(1);
+        list.push_back(3);
+        list.push_back(4);
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.front(), Some(1));
+        assert_eq!(list.back(), Some(4));
+        assert_eq!(values(&list), vec![1, 2, 3, 4]);
+
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_back(), Some(4));
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.pop_front(), Some(2));
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.pop_back(), None);
+        assert!(list.is_empty());
+        assert_eq!(list.front(), None);
+        assert_eq!(list.back(), None);
+
+        // still linked correctly after running empty
+        list.push_back(5);
+        list.push_front(4);
+        assert_eq!(values(&list), vec![4, 5]);
+    }
+
+    #[test]
+    fn traits() {
+        let mut list: List<i32> = (1..=3).collect();
+        list.extend(vec![4, 5]);
+        assert_eq!(format!("{:?}", list), "[1, 2, 3, 4, 5]");
+        assert_eq!(format!("{:?}", List::<i32>::default()), "[]");
+
+        let copy = list.clone();
+        list.pop_front();
+        assert_eq!(values(&copy), vec![1, 2, 3, 4, 5]);
+
+        let mut iter = copy.into_iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next_back(), Some(5));
+        assert_eq!(iter.collect::<Vec<_>>(), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn drop_order() {
+        let counter = DropCounter::new();
+        let list = List::new();
+        for id in 0..5 {
+            list.push_back(counter.track(id));
+        }
+        drop(list.pop_back());
+        assert_eq!(counter.dropped(), vec![4]);
+
+        drop(list);
+        assert_eq!(counter.dropped(), vec![4, 0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn drop_panic() {
+        let counter = DropCounter::new();
+        let list = List::new();
+        list.push_back(counter.track(0));
+        list.push_back(counter.panic_on_drop(1));
+        list.push_back(counter.track(2));
+
+        assert!(panics(move || drop(list)));
+        assert_eq!(counter.dropped(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn drop_panic_long() {
+        // the panic comes first, the million nodes behind it must still be
+        // dropped one by one and not recursively
+        let counter = DropCounter::new();
+        let list = List::new();
+        list.push_back(counter.panic_on_drop(0));
+        for id in 1..=1_000_000 {
+            list.push_back(counter.track(id));
+        }
+        assert!(panics(move || drop(list)));
+        assert_eq!(counter.count(), 1_000_001);
+    }
+
+    #[test]
+    fn no_leaks() {
+        let before = live_allocations();
+        let list = List::new();
+        for i in 0..100_000 {
+            list.push_back(i);
+            list.push_front(i);
+        }
+        for _ in 0..1000 {
+            list.pop_back();
+            list.pop_front();
+        }
+        drop(list);
+        assert_eq!(live_allocations(), before);
+    }
+
+    #[test]
+    fn stress_both_ends() {
+        const THREADS: usize = 4;
+        const PER_THREAD: usize = 10_000;
+        const TOTAL: usize = THREADS * PER_THREAD;
+
+        let list = List::new();
+        let popped = AtomicUsize::new(0);
+        let seen = Mutex::new(Vec::with_capacity(TOTAL));
+
+        thread::scope(|s| {
+            for t in 0..THREADS {
+                let list = &list;
+                s.spawn(move || {
+                    for i in t * PER_THREAD..(t + 1) * PER_THREAD {
+                        if i % 2 == t % 2 {
+                            list.push_front(i);
+                        } else {
+                            list.push_back(i);
+                        }
+                    }
+                });
+            }
+            for t in 0..THREADS {
+                let (list, popped, seen) = (&list, &popped, &seen);
+                s.spawn(move || {
+                    let mut mine = Vec::new();
+                    let mut from_front = t % 2 == 0;
+                    while popped.load(Ordering::Relaxed) < TOTAL {
+                        let elem = if from_front {
+                            list.pop_front()
+                        } else {
+                            list.pop_back()
+                        };
+                        match elem {
+                            Some(elem) => {
+                                popped.fetch_add(1, Ordering::Relaxed);
+                                mine.push(elem);
+                            }
+                            None => thread::yield_now(),
+                        }
+                        from_front = !from_front;
+                    }
+                    seen.lock().unwrap().extend(mine);
+                });
+            }
+        });
+
+        // every element came out exactly once
+        let mut seen = seen.into_inner().unwrap();
+        seen.sort();
+        assert_eq!(seen, (0..TOTAL).collect::<Vec<_>>());
+        assert!(list.is_empty());
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.pop_back(), None);
+    }
+
+    #[test]
+    fn stress_queue_order() {
+        const N: i32 = 50_000;
+        let list = List::new();
+
+        // a producer at one end and a consumer at the other, only ever
+        // meeting on the last few elements: the queue stays in order
+        thread::scope(|s| {
+            s.spawn(|| (0..N).for_each(|i| list.push_back(i)));
+            let mut got = Vec::new();
+            while got.len() < N as usize {
+                match list.pop_front() {
+                    Some(elem) => got.push(elem),
+                    None => thread::yield_now(),
+                }
+            }
+            assert_eq!(got, (0..N).collect::<Vec<_>>());
+        });
+
+        // and the other way around
+        thread::scope(|s| {
+            s.spawn(|| (0..N).for_each(|i| list.push_front(i)));
+            let mut got = Vec::new();
+            while got.len() < N as usize {
+                match list.pop_back() {
+                    Some(elem) => got.push(elem),
+                    None => thread::yield_now(),
+                }
+            }
+            assert_eq!(got, (0..N).collect::<Vec<_>>());
+        });
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn stress_peek() {
+        const ROUNDS: usize = 100_000;
+        let list: List<i32> = (0..2).collect();
+
+        // one end keeps popping and pushing back, the list is never empty
+        // and a peek at that end must always see something
+        thread::scope(|s| {
+            s.spawn(|| {
+                for _ in 0..ROUNDS {
+                    let elem = list.pop_front().unwrap();
+                    list.push_front(elem);
+                }
+            });
+            for _ in 0..ROUNDS {
+                assert!(list.front().is_some());
+            }
+        });
+        thread::scope(|s| {
+            s.spawn(|| {
+                for _ in 0..ROUNDS {
+                    let elem = list.pop_back().unwrap();
+                    list.push_back(elem);
+                }
+            });
+            for _ in 0..ROUNDS {
+                assert!(list.back().is_some());
+            }
+        });
+        assert_eq!(values(&list), vec![0, 1]);
+    }
+
+    #[test]
+    fn walk_while_popping() {
+        const N: i32 = 20_000;
+        let list: List<i32> = (0..N).collect();
+
+        thread::scope(|s| {
+            s.spawn(|| while list.pop_back().is_some() {});
+            // whatever the walk sees is a prefix of the list
+            for _ in 0..10 {
+                let snapshot = values(&list);
+                assert_eq!(snapshot, (0..snapshot.len() as i32).collect::<Vec<_>>());
+            }
+        });
+        assert!(list.is_empty());
+    }
+}
@@ -0,0 +1,164 @@
+// Instrumented element types shared by the unit tests of every list.
+//
+// A `DropCounter` is a shared log, every `Tracked` element created from it
+// appends its id to the log when dropped. That lets a test check both how
+// many elements were dropped (no leak, no double free) and in which order.
+// Elements can be armed to panic on drop or on clone, so we can exercise
+// the unwinding paths of `Drop`, `Clone` and `Extend`.
+//
+// The test binary also runs on a counting allocator: `live_allocations`
+// tells how many heap blocks the current thread has allocated and not freed
+// yet, so a test can check a list gave all its memory back. That is a net
+// count, freeing a node and allocating it again leaves it as it was, so
+// `total_allocations` counts every allocation ever made instead.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::{Cell, RefCell};
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
+#[derive(Clone, Default)]
+pub struct DropCounter {
+    log: Rc<RefCell<Vec<usize>>>,
+}
+
+pub struct Tracked {
+    pub id: usize,
+    log: Rc<RefCell<Vec<usize>>>,
+    panic_on_drop: bool,
+    panic_on_clone: bool,
+}
+
+impl DropCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn track(&self, id: usize) -> Tracked {
+        self.make(id, false, false)
+    }
+
+    // records its drop, then panics
+    pub fn panic_on_drop(&self, id: usize) -> Tracked {
+        self.make(id, true, false)
+    }
+
+    // panics when cloned, drops normally
+    pub fn panic_on_clone(&self, id: usize) -> Tracked {
+        self.make(id, false, true)
+    }
+
+    fn make(&self, id: usize, panic_on_drop: bool, panic_on_clone: bool) -> Tracked {
+        Tracked {
+            id,
+            log: Rc::clone(&self.log),
+            panic_on_drop,
+            panic_on_clone,
+        }
+    }
+
+    // ids of dropped elements, in drop order
+    pub fn dropped(&self) -> Vec<usize> {
+        self.log.borrow().clone()
+    }
+
+    pub fn count(&self) -> usize {
+        self.log.borrow().len()
+    }
+}
+
+impl Clone for Tracked {
+    fn clone(&self) -> Self {
+        if self.panic_on_clone {
+            panic!("Tracked({}) panicked in clone", self.id);
+        }
+        Tracked {
+            id: self.id,
+            log: Rc::clone(&self.log),
+            panic_on_drop: self.panic_on_drop,
+            panic_on_clone: false,
+        }
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.log.borrow_mut().push(self.id);
+        // never start a second panic while unwinding, that would abort
+        if self.panic_on_drop && !std::thread::panicking() {
+            panic!("Tracked({}) panicked in drop", self.id);
+        }
+    }
+}
+
+// runs `f` and returns whether it panicked
+pub fn panics<F: FnOnce()>(f: F) -> bool {
+    panic::catch_unwind(AssertUnwindSafe(f)).is_err()
+}
+
+struct CountingAlloc;
+
+thread_local! {
+    // const initialized, so touching it from the allocator never allocates
+    static LIVE: Cell<isize> = const { Cell::new(0) };
+    static TOTAL: Cell<usize> = const { Cell::new(0) };
+}
+
+fn count(delta: isize) {
+    // the slots are gone while the thread shuts down, nothing to count by then
+    let _ = LIVE.try_with(|live| live.set(live.get() + delta));
+    if delta > 0 {
+        count_total();
+    }
+}
+
+fn count_total() {
+    let _ = TOTAL.try_with(|total| total.set(total.get() + 1));
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            count(1);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            count(1);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        count(-1);
+    }
+
+    // a block that moves is still one block, but it was allocated again
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let ptr = System.realloc(ptr, layout, new_size);
+        if !ptr.is_null() {
+            count_total();
+        }
+        ptr
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+// heap blocks allocated and not yet freed by the current thread, only
+// meaningful as the difference between two calls
+pub fn live_allocations() -> isize {
+    LIVE.with(|live| live.get())
+}
+
+// heap blocks allocated (or reallocated) by the current thread so far, freed
+// or not, only meaningful as the difference between two calls
+pub fn total_allocations() -> usize {
+    TOTAL.with(|total| total.get())
+}
@@ -13,8 +13,21 @@
 //      That is, if T is Sync, &T is Send. Safe in this case means it's impossible to cause data races
 //   6. Rc is a "cell", it lets you mutate through a shared reference (modify the counter),
 //      it only works in single-threaded context. Arc uses an "atomic", the counter is thread-safe
+//   7. Nodes never change, so a derived list that ends like its input can point at the input's
+//      nodes instead of copying them: `drop`, `concat` and `filter` share the longest suffix they can.
+//      Everything in front of that suffix is new, and needs T: Clone
+//   8. Rc::get_mut gives a &mut when nobody else holds the node, Rc::make_mut clones the node
+//      first if somebody does (copy on write). A cloned node still shares its `next`, so going
+//      down a path with make_mut copies exactly the shared part of it
+//   9. Two nodes are equal lists if their values are equal and their `next` are the same node.
+//      A ListFactory remembers every node it made (hash-consing), and hands out the existing one
+//      instead of a duplicate. Equal lists from one factory are then the very same nodes.
+//      The table only holds Weak refs, it never keeps a list alive
 
-use std::rc::Rc;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::rc::{Rc, Weak};
 
 pub struct List<T> {
     head: Link<T>,
@@ -22,7 +35,7 @@ pub struct List<T> {
 
 type Link<T> = Option<Rc<Node<T>>>;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct Node<T> {
     value: T,
     next: Link<T>,
@@ -54,26 +67,299 @@ impl<T> List<T> {
     pub fn head(&self) -> Option<&T> {
         self.head.as_ref().map(|node| &node.value)
     }
+
+    // whether both lists are the very same nodes
+    pub fn ptr_eq(&self, other: &List<T>) -> bool {
+        match (&self.head, &other.head) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+// in-place updates, for a list that holds its nodes alone
+impl<T> List<T> {
+    // None if the list is empty or the head node is shared
+    pub fn head_mut(&mut self) -> Option<&mut T> {
+        self.head
+            .as_mut()
+            .and_then(Rc::get_mut)
+            .map(|node| &mut node.value)
+    }
+
+    // prepend without going through a new List
+    pub fn push_front_in_place(&mut self, elem: T) {
+        let next = self.head.take();
+        self.head = Some(Rc::new(Node { value: elem, next }));
+    }
+}
+
+impl<T: Clone> List<T> {
+    // The element at `index`, after copying the nodes up to it that other
+    // lists still share. Other lists don't see the change.
+    pub fn make_mut_at(&mut self, index: usize) -> Option<&mut T> {
+        // check first, so we don't copy anything for nothing
+        self.iter().nth(index)?;
+        let mut link = &mut self.head;
+        for _ in 0..index {
+            link = &mut Rc::make_mut(link.as_mut()?).next;
+        }
+        link.as_mut().map(|node| &mut Rc::make_mut(node).value)
+    }
+}
+
+// derived lists, each one is a new list and leaves `self` alone
+impl<T> List<T> {
+    // a list of `values`, in order, in front of the nodes of `tail`
+    fn build(values: Vec<T>, tail: Link<T>) -> List<T> {
+        let head = values
+            .into_iter()
+            .rev()
+            .fold(tail, |next, value| Some(Rc::new(Node { value, next })));
+        List { head }
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> List<U> {
+        List::build(self.iter().map(f).collect(), None)
+    }
+
+    pub fn fold<B, F: FnMut(B, &T) -> B>(&self, init: B, f: F) -> B {
+        self.iter().fold(init, f)
+    }
+
+    // without the first `n` elements, shares all the remaining nodes
+    pub fn drop(&self, n: usize) -> List<T> {
+        let mut link = &self.head;
+        for _ in 0..n {
+            match link {
+                Some(node) => link = &node.next,
+                None => break,
+            }
+        }
+        List { head: link.clone() }
+    }
+}
+
+impl<T: Clone> List<T> {
+    // Keeps the elements matching `keep`, everything after the last removed
+    // element is shared, the whole list if nothing is removed.
+    pub fn filter<P: FnMut(&T) -> bool>(&self, mut keep: P) -> List<T> {
+        let mut copied = Vec::new();
+        // kept since the last removed element, they may end up shared
+        let mut pending = Vec::new();
+        let mut shared = &self.head;
+        let mut link = &self.head;
+        while let Some(node) = link {
+            if keep(&node.value) {
+                pending.push(&node.value);
+            } else {
+                copied.append(&mut pending);
+                shared = &node.next;
+            }
+            link = &node.next;
+        }
+        List::build(copied.into_iter().cloned().collect(), shared.clone())
+    }
+
+    pub fn reverse(&self) -> List<T> {
+        let head = self.iter().fold(None, |next, value| {
+            Some(Rc::new(Node {
+                value: value.clone(),
+                next,
+            }))
+        });
+        List { head }
+    }
+
+    // pairs up the elements, as long as the shorter list
+    pub fn zip<U: Clone>(&self, other: &List<U>) -> List<(T, U)> {
+        let pairs = self.iter().zip(other.iter());
+        List::build(pairs.map(|(a, b)| (a.clone(), b.clone())).collect(), None)
+    }
+
+    // the first `n` elements, shared if that is the whole list
+    pub fn take(&self, n: usize) -> List<T> {
+        if self.drop(n).head.is_none() {
+            return self.clone();
+        }
+        List::build(self.iter().take(n).cloned().collect(), None)
+    }
+
+    // the elements of `self` followed by `other`, `other` is shared
+    pub fn concat(&self, other: &List<T>) -> List<T> {
+        List::build(self.iter().cloned().collect(), other.head.clone())
+    }
+}
+
+// const ref iterator, the nodes are only ever shared so this is all we can have
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+}
+
+impl<T> List<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head.as_deref(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| {
+            self.next = node.next.as_deref();
+            &node.value
+        })
+    }
+}
+
+// cloning a persistent list is cheap, the clone shares every node
+impl<T> Clone for List<T> {
+    fn clone(&self) -> Self {
+        List {
+            head: self.head.clone(),
+        }
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> List<T> {
+    // Takes the head node out if no other list shares it, its `next` becomes
+    // the head. Stops at a shared node, dropping it is its other owners' job.
+    fn unlink_head(&mut self) -> Option<Node<T>> {
+        let shared_node = self.head.take()?;
+        match Rc::try_unwrap(shared_node) {
+            Ok(mut node) => {
+                self.head = node.next.take();
+                Some(node)
+            }
+            // only drops a reference count
+            Err(_) => None,
+        }
+    }
 }
 
 impl<T> Drop for List<T> {
     fn drop(&mut self) {
-        let mut head = self.head.take();
-        while let Some(shared_node) = head {
-            // try do drop the value
-            match Rc::try_unwrap(shared_node) {
-                Ok(node) => {
-                    head = node.next;
-                }
-                Err(_) => break,
-            };
+        // if dropping a value panics, keep unlinking the rest while unwinding,
+        // a chain left in a local would be dropped recursively and blow the stack
+        struct DropGuard<'a, T>(&'a mut List<T>);
+        impl<'a, T> Drop for DropGuard<'a, T> {
+            fn drop(&mut self) {
+                while self.0.unlink_head().is_some() {}
+            }
+        }
+
+        while let Some(node) = self.unlink_head() {
+            let guard = DropGuard(self);
+            drop(node);
+            std::mem::forget(guard);
+        }
+    }
+}
+
+// Hash-consing: a factory that never builds two equal nodes. Lists built
+// only through the same factory are equal exactly when they are `ptr_eq`.
+pub struct ListFactory<T> {
+    // by hash of (value, address of next), a bucket holds the colliding nodes
+    table: HashMap<u64, Vec<Weak<Node<T>>>>,
+    hasher: RandomState,
+    // Weak refs in the table, live or not, and how many trigger a purge
+    entries: usize,
+    purge_at: usize,
+}
+
+const MIN_PURGE_AT: usize = 64;
+
+impl<T: Eq + Hash> ListFactory<T> {
+    pub fn new() -> Self {
+        ListFactory {
+            table: HashMap::new(),
+            hasher: RandomState::new(),
+            entries: 0,
+            purge_at: MIN_PURGE_AT,
+        }
+    }
+
+    // `list.prepend(elem)`, reusing the node if this factory already made it
+    pub fn prepend(&mut self, list: &List<T>, elem: T) -> List<T> {
+        // a live node's `next` keeps its target alive, the address is unique
+        let next = list.head.as_ref().map_or(std::ptr::null(), Rc::as_ptr);
+        let hash = self.hasher.hash_one((&elem, next));
+        let bucket = self.table.entry(hash).or_default();
+        for node in bucket.iter().filter_map(Weak::upgrade) {
+            if node.value == elem && node.next.as_ref().map_or(std::ptr::null(), Rc::as_ptr) == next
+            {
+                return List { head: Some(node) };
+            }
         }
+
+        let node = Rc::new(Node {
+            value: elem,
+            next: list.head.clone(),
+        });
+        bucket.push(Rc::downgrade(&node));
+        self.entries += 1;
+        if self.entries >= self.purge_at {
+            self.purge();
+        }
+        List { head: Some(node) }
+    }
+
+    // an interned list of `items`, in order
+    pub fn list<I>(&mut self, items: I) -> List<T>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: DoubleEndedIterator,
+    {
+        items
+            .into_iter()
+            .rev()
+            .fold(List::new(), |list, elem| self.prepend(&list, elem))
+    }
+
+    // Forgets the nodes that were dropped since. Runs by itself whenever the
+    // table doubled, so dead entries never outnumber the live ones for long.
+    pub fn purge(&mut self) {
+        self.table.retain(|_, bucket| {
+            bucket.retain(|node| node.strong_count() > 0);
+            !bucket.is_empty()
+        });
+        self.entries = self.table.values().map(Vec::len).sum();
+        self.purge_at = MIN_PURGE_AT.max(2 * self.entries);
+    }
+
+    // live nodes made by this factory
+    pub fn len(&self) -> usize {
+        self.table
+            .values()
+            .flatten()
+            .filter(|node| node.strong_count() > 0)
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Eq + Hash> Default for ListFactory<T> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::List;
+    use super::{List, ListFactory};
+    use crate::test_helpers::{live_allocations, panics, DropCounter};
 
     #[test]
     fn basics() {
@@ -96,4 +382,288 @@ mod test {
         let list = list.tail();
         assert_eq!(list.head(), None);
     }
+
+    #[test]
+    fn drop_order() {
+        let counter = DropCounter::new();
+        let mut list = List::new();
+        for id in 0..5 {
+            list = list.prepend(counter.track(id));
+        }
+        let tail = list.tail();
+
+        // the shared tail keeps the rest of the nodes alive
+        drop(list);
+        assert_eq!(counter.dropped(), vec![4]);
+
+        drop(tail);
+        assert_eq!(counter.dropped(), vec![4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn drop_panic() {
+        let counter = DropCounter::new();
+        let list = List::new()
+            .prepend(counter.track(0))
+            .prepend(counter.track(1))
+            .prepend(counter.panic_on_drop(2))
+            .prepend(counter.track(3))
+            .prepend(counter.track(4));
+
+        assert!(panics(move || drop(list)));
+        assert_eq!(counter.dropped(), vec![4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn drop_panic_long() {
+        // the panic comes first, the million nodes behind it must still be
+        // dropped one by one and not recursively
+        let counter = DropCounter::new();
+        let list = (0..1_000_000)
+            .fold(List::new(), |list, id| list.prepend(counter.track(id)))
+            .prepend(counter.panic_on_drop(1_000_000));
+
+        assert!(panics(move || drop(list)));
+        assert_eq!(counter.count(), 1_000_001);
+    }
+
+    #[test]
+    fn iter_and_clone() {
+        let list = List::new().prepend(1).prepend(2).prepend(3);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&3, &2, &1]);
+
+        let copy = list.clone();
+        let list = list.tail();
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![&2, &1]);
+        assert_eq!(copy.iter().collect::<Vec<_>>(), vec![&3, &2, &1]);
+        // the clone and the tail share their nodes
+        assert!(std::ptr::eq(
+            copy.tail().head().unwrap(),
+            list.head().unwrap()
+        ));
+        assert_eq!(List::<i32>::new().iter().next(), None);
+    }
+
+    fn list_of(values: &[i32]) -> List<i32> {
+        values
+            .iter()
+            .rev()
+            .fold(List::new(), |list, &v| list.prepend(v))
+    }
+
+    fn values(list: &List<i32>) -> Vec<i32> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn map_fold_reverse_zip() {
+        let list = list_of(&[1, 2, 3]);
+        assert_eq!(values(&list.map(|x| x * 10)), vec![10, 20, 30]);
+        assert_eq!(list.map(|x| x.to_string()).head(), Some(&"1".to_string()));
+        assert_eq!(list.fold(0, |acc, x| acc * 10 + x), 123);
+        assert_eq!(values(&list.reverse()), vec![3, 2, 1]);
+        assert_eq!(values(&list), vec![1, 2, 3]);
+
+        let zipped = list.zip(&list_of(&[4, 5]));
+        assert_eq!(zipped.iter().collect::<Vec<_>>(), vec![&(1, 4), &(2, 5)]);
+        assert!(List::<i32>::new().reverse().head().is_none());
+    }
+
+    #[test]
+    fn take_and_drop() {
+        let list = list_of(&[1, 2, 3, 4]);
+        assert_eq!(values(&list.take(2)), vec![1, 2]);
+        assert_eq!(values(&list.drop(2)), vec![3, 4]);
+        assert_eq!(values(&list.take(0)), vec![]);
+        assert_eq!(values(&list.drop(10)), vec![]);
+
+        // drop is always a suffix, take only when it takes everything
+        assert!(list.drop(2).ptr_eq(&list.tail().tail()));
+        assert!(list.drop(0).ptr_eq(&list));
+        assert!(list.take(4).ptr_eq(&list));
+        assert!(list.take(10).ptr_eq(&list));
+        assert!(!list.take(3).ptr_eq(&list));
+    }
+
+    #[test]
+    fn filter_shares_suffix() {
+        let list = list_of(&[1, 2, 3, 4, 5, 6]);
+
+        let odd = list.filter(|x| x % 2 == 1);
+        assert_eq!(values(&odd), vec![1, 3, 5]);
+
+        // nothing removed, nothing copied
+        let all = list.filter(|_| true);
+        assert!(all.ptr_eq(&list));
+
+        // only removed up front, the rest is shared
+        let big = list.filter(|&x| x > 2);
+        assert_eq!(values(&big), vec![3, 4, 5, 6]);
+        assert!(big.ptr_eq(&list.drop(2)));
+
+        // [1, 2] are copied in front of the shared [4, 5, 6]
+        let no_three = list.filter(|&x| x != 3);
+        assert_eq!(values(&no_three), vec![1, 2, 4, 5, 6]);
+        assert!(no_three.drop(2).ptr_eq(&list.drop(3)));
+        assert!(!no_three.ptr_eq(&list));
+
+        // the predicate runs once per element, in order
+        let mut seen = Vec::new();
+        list.filter(|&x| {
+            seen.push(x);
+            true
+        });
+        assert_eq!(seen, vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn concat_shares_other() {
+        let front = list_of(&[1, 2]);
+        let back = list_of(&[3, 4]);
+        let both = front.concat(&back);
+        assert_eq!(values(&both), vec![1, 2, 3, 4]);
+        assert!(both.drop(2).ptr_eq(&back));
+        assert!(List::new().concat(&back).ptr_eq(&back));
+        assert_eq!(values(&front.concat(&List::new())), vec![1, 2]);
+    }
+
+    #[test]
+    fn derived_drop_iteratively() {
+        let mut list = List::new();
+        for i in 0..200_000 {
+            list = list.prepend(i);
+        }
+        let filtered = list.filter(|&x| x != 100_000);
+        let mapped = list.map(|x| x + 1);
+        let reversed = list.reverse();
+        drop(list);
+        drop(filtered);
+        drop(mapped);
+        drop(reversed);
+    }
+
+    #[test]
+    fn head_mut() {
+        let mut list = list_of(&[1, 2]);
+        *list.head_mut().unwrap() = 10;
+        assert_eq!(values(&list), vec![10, 2]);
+
+        // shared head, no in-place update
+        let copy = list.clone();
+        assert_eq!(list.head_mut(), None);
+        drop(copy);
+        assert_eq!(list.head_mut(), Some(&mut 10));
+
+        // a shared tail doesn't matter for the head
+        let tail = list.tail();
+        assert_eq!(list.head_mut(), Some(&mut 10));
+        assert_eq!(values(&tail), vec![2]);
+        assert_eq!(List::<i32>::new().head_mut(), None);
+    }
+
+    #[test]
+    fn push_front_in_place() {
+        let mut list = List::new();
+        list.push_front_in_place(2);
+        let shared = list.clone();
+        list.push_front_in_place(1);
+        assert_eq!(values(&list), vec![1, 2]);
+        assert_eq!(values(&shared), vec![2]);
+        assert!(list.tail().ptr_eq(&shared));
+    }
+
+    #[test]
+    fn make_mut_at() {
+        let mut list = list_of(&[1, 2, 3, 4, 5]);
+
+        // owned alone: no copy at all
+        let before = live_allocations();
+        *list.make_mut_at(3).unwrap() = 40;
+        assert_eq!(live_allocations(), before);
+        assert_eq!(values(&list), vec![1, 2, 3, 40, 5]);
+        assert_eq!(list.make_mut_at(5), None);
+        assert_eq!(live_allocations(), before);
+
+        // shared: only the nodes up to the index are copied
+        let copy = list.clone();
+        *list.make_mut_at(2).unwrap() = 30;
+        assert_eq!(live_allocations() - before, 3);
+        assert_eq!(values(&list), vec![1, 2, 30, 40, 5]);
+        assert_eq!(values(&copy), vec![1, 2, 3, 40, 5]);
+        assert!(list.drop(3).ptr_eq(&copy.drop(3)));
+
+        // now the copied prefix belongs to `list` alone
+        *list.make_mut_at(1).unwrap() = 20;
+        assert_eq!(live_allocations() - before, 3);
+        assert_eq!(values(&list), vec![1, 20, 30, 40, 5]);
+
+        // shared only in the middle: the prefix in front of it is copied too,
+        // since a node can't point to a copy without being changed itself
+        let mut list = list_of(&[1, 2, 3]);
+        let tail = list.tail();
+        *list.make_mut_at(2).unwrap() = 30;
+        assert_eq!(values(&list), vec![1, 2, 30]);
+        assert_eq!(values(&tail), vec![2, 3]);
+    }
+
+    #[test]
+    fn factory_dedups() {
+        let mut factory = ListFactory::new();
+        let a = factory.list(vec![1, 2, 3]);
+        let b = factory.list(vec![1, 2, 3]);
+        let c = factory.list(vec![0, 2, 3]);
+        let d = factory.list(vec![1, 2, 4]);
+
+        assert!(a.ptr_eq(&b));
+        assert!(!a.ptr_eq(&c));
+        assert!(a.drop(1).ptr_eq(&c.drop(1)));
+        // equal values in front of different suffixes are different nodes
+        assert!(!a.ptr_eq(&d));
+        assert_eq!(values(&d), vec![1, 2, 4]);
+        // [3], [2, 3], [1, 2, 3], [0, 2, 3], [4], [2, 4], [1, 2, 4]
+        assert_eq!(factory.len(), 7);
+
+        // a list that isn't interned still works, it just can't be shared
+        let plain = list_of(&[2, 3]);
+        let e = factory.prepend(&plain, 1);
+        assert!(!e.ptr_eq(&a));
+        assert!(e.ptr_eq(&factory.prepend(&plain, 1)));
+        assert_eq!(values(&e), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn factory_forgets_dropped_nodes() {
+        let before = live_allocations();
+        let mut factory = ListFactory::new();
+        let keep = factory.list(0..10);
+        let mut list = List::new();
+        for i in 0..200_000 {
+            list = factory.prepend(&list, i);
+        }
+        assert_eq!(factory.len(), 200_010);
+
+        // the Weak refs don't get in the way of the iterative drop
+        drop(list);
+        assert_eq!(factory.len(), 10);
+        factory.purge();
+        assert_eq!(factory.entries, 10);
+
+        // the kept nodes are still found
+        assert!(factory.list(0..10).ptr_eq(&keep));
+        drop(keep);
+        drop(factory);
+        assert_eq!(live_allocations(), before);
+    }
+
+    #[test]
+    fn factory_purges_by_itself() {
+        let mut factory = ListFactory::new();
+        for round in 0..1000 {
+            let list = factory.list(vec![round, round + 1]);
+            drop(list);
+        }
+        // dead entries are bounded by the live ones (none) and the minimum
+        assert!(factory.entries < 2 * super::MIN_PURGE_AT);
+        assert!(factory.is_empty());
+    }
 }
@@ -0,0 +1,985 @@
+// "An Unrolled Deque", the node/pointer discipline of "sixth.rs" with a
+// small array in every node. To learn about:
+//   1) Cache-friendly linked structures
+//   2) MaybeUninit and partially initialized arrays
+//   3) const generics
+
+// Takeaways:
+//   1. A node holds up to N elements in `[MaybeUninit<T>; N]`, only `len` slots
+//      from `start` on, wrapping around, are initialized. Iteration walks an
+//      array most of the time and only chases a pointer every N elements.
+//   2. Each node is a small ring buffer, so pushing or popping at either end of
+//      a node moves nothing else: the deque's ends are O(1) like "sixth.rs".
+//      Inserting or removing in the middle shifts the shorter side.
+//   3. MaybeUninit<T> never drops its content, so the node has no destructor.
+//      We must drop exactly the initialized slots ourselves, once.
+//   4. Inserting into a full node splits it in two half-full nodes. Removing
+//      from a node that drops below half full merges it with a neighbor if
+//      they fit in one node, so nodes stay reasonably dense.
+//   5. Never create a `&mut` to a whole node (or its array) while a `&mut T`
+//      into it may be alive, that would invalidate it under stacked borrows.
+//      Go through `addr_of_mut!` to get a raw pointer to a single slot.
+
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::ptr::{self, NonNull};
+
+pub struct UnrolledList<T, const N: usize> {
+    front: Link<T, N>,
+    back: Link<T, N>,
+    len: usize,
+    // We semantically store values of T by-value.
+    _boo: PhantomData<T>,
+}
+
+type Link<T, const N: usize> = Option<NonNull<Node<T, N>>>;
+
+struct Node<T, const N: usize> {
+    prev: Link<T, N>,
+    next: Link<T, N>,
+    // the elements are elems[start..start + len], modulo N
+    start: usize,
+    len: usize,
+    elems: [MaybeUninit<T>; N],
+}
+
+impl<T, const N: usize> Node<T, N> {
+    fn new() -> NonNull<Self> {
+        let node = Box::new(Node {
+            prev: None,
+            next: None,
+            start: 0,
+            len: 0,
+            elems: [const { MaybeUninit::uninit() }; N],
+        });
+        // Box never returns null
+        unsafe { NonNull::new_unchecked(Box::into_raw(node)) }
+    }
+
+    // raw pointer to the slot of element `index`, counted from `start`,
+    // without borrowing the whole node
+    unsafe fn slot(node: NonNull<Self>, index: usize) -> *mut T {
+        debug_assert!(index < N);
+        // both are below N, no need for a division
+        let mut at = (*node.as_ptr()).start + index;
+        if at >= N {
+            at -= N;
+        }
+        (ptr::addr_of_mut!((*node.as_ptr()).elems) as *mut T).add(at)
+    }
+
+    // moves element `from` to the slot of element `to`, which must be free
+    unsafe fn move_elem(node: NonNull<Self>, from: usize, to: usize) {
+        ptr::copy_nonoverlapping(Self::slot(node, from), Self::slot(node, to), 1);
+    }
+
+    // Makes room at `index` by shifting the elements on its shorter side
+    // one slot outwards, and writes `elem` there.
+    unsafe fn insert(node: NonNull<Self>, index: usize, elem: T) {
+        let len = (*node.as_ptr()).len;
+        debug_assert!(len < N && index <= len);
+        if index < len - index {
+            // the slot before `start` becomes element 0
+            (*node.as_ptr()).start = ((*node.as_ptr()).start + N - 1) % N;
+            for i in 0..index {
+                Self::move_elem(node, i + 1, i);
+            }
+        } else {
+            for i in (index..len).rev() {
+                Self::move_elem(node, i, i + 1);
+            }
+        }
+        ptr::write(Self::slot(node, index), elem);
+        (*node.as_ptr()).len = len + 1;
+    }
+
+    // Reads element `index` and closes the gap by shifting the elements on
+    // its shorter side one slot inwards.
+    unsafe fn remove(node: NonNull<Self>, index: usize) -> T {
+        let len = (*node.as_ptr()).len;
+        debug_assert!(index < len);
+        let elem = ptr::read(Self::slot(node, index));
+        if index < len - 1 - index {
+            for i in (0..index).rev() {
+                Self::move_elem(node, i, i + 1);
+            }
+            (*node.as_ptr()).start = ((*node.as_ptr()).start + 1) % N;
+        } else {
+            for i in index + 1..len {
+                Self::move_elem(node, i, i - 1);
+            }
+        }
+        (*node.as_ptr()).len = len - 1;
+        elem
+    }
+
+    // moves elements `from..` of `src` to the end of `dst`, `src` keeps the
+    // ones before
+    unsafe fn move_tail(src: NonNull<Self>, from: usize, dst: NonNull<Self>) {
+        let count = (*src.as_ptr()).len - from;
+        let dst_len = (*dst.as_ptr()).len;
+        debug_assert!(dst_len + count <= N);
+        for i in 0..count {
+            ptr::copy_nonoverlapping(Self::slot(src, from + i), Self::slot(dst, dst_len + i), 1);
+        }
+        (*src.as_ptr()).len = from;
+        (*dst.as_ptr()).len = dst_len + count;
+    }
+
+    // the elements as two slices, the second one is empty unless they wrap
+    unsafe fn as_slices(node: &mut Self) -> (*mut [T], *mut [T]) {
+        let elems = node.elems.as_mut_ptr() as *mut T;
+        let first = node.len.min(N - node.start);
+        (
+            ptr::slice_from_raw_parts_mut(elems.add(node.start), first),
+            ptr::slice_from_raw_parts_mut(elems, node.len - first),
+        )
+    }
+}
+
+impl<T, const N: usize> UnrolledList<T, N> {
+    pub fn new() -> Self {
+        assert!(N >= 2, "an unrolled node must hold at least 2 elements");
+        Self {
+            front: None,
+            back: None,
+            len: 0,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push_front(&mut self, elem: T) {
+        unsafe {
+            let node = match self.front {
+                Some(node) if (*node.as_ptr()).len < N => node,
+                _ => self.link_after(None),
+            };
+            Node::insert(node, 0, elem);
+        }
+        self.len += 1;
+    }
+
+    pub fn push_back(&mut self, elem: T) {
+        unsafe {
+            let node = match self.back {
+                Some(node) if (*node.as_ptr()).len < N => node,
+                back => self.link_after(back),
+            };
+            let len = (*node.as_ptr()).len;
+            Node::insert(node, len, elem);
+        }
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.front.map(|node| unsafe {
+            let elem = Node::remove(node, 0);
+            if (*node.as_ptr()).len == 0 {
+                self.unlink(node);
+            }
+            self.len -= 1;
+            elem
+        })
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.back.map(|node| unsafe {
+            let elem = Node::remove(node, (*node.as_ptr()).len - 1);
+            if (*node.as_ptr()).len == 0 {
+                self.unlink(node);
+            }
+            self.len -= 1;
+            elem
+        })
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.front.map(|node| unsafe { &*Node::slot(node, 0) })
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.front.map(|node| unsafe { &mut *Node::slot(node, 0) })
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.back
+            .map(|node| unsafe { &*Node::slot(node, (*node.as_ptr()).len - 1) })
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.back
+            .map(|node| unsafe { &mut *Node::slot(node, (*node.as_ptr()).len - 1) })
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        unsafe {
+            let (node, offset) = self.locate(index);
+            Some(&*Node::slot(node, offset))
+        }
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len {
+            return None;
+        }
+        unsafe {
+            let (node, offset) = self.locate(index);
+            Some(&mut *Node::slot(node, offset))
+        }
+    }
+
+    // Inserts `elem` so that it ends up at `index`, splitting the node it
+    // lands in if that one is full.
+    //
+    // Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, elem: T) {
+        assert!(index <= self.len, "insertion index out of bounds");
+        if index == self.len {
+            return self.push_back(elem);
+        }
+
+        unsafe {
+            let (mut node, mut offset) = self.locate(index);
+            if (*node.as_ptr()).len == N {
+                // split: the upper half moves to a new node right after it
+                let half = N / 2;
+                let upper = self.link_after(Some(node));
+                Node::move_tail(node, half, upper);
+
+                if offset > half {
+                    node = upper;
+                    offset -= half;
+                }
+            }
+            Node::insert(node, offset, elem);
+        }
+        self.len += 1;
+    }
+
+    // Removes the element at `index`, merging its node with a neighbor if
+    // it becomes less than half full and they fit in one node.
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.len {
+            return None;
+        }
+
+        unsafe {
+            let (node, offset) = self.locate(index);
+            let elem = Node::remove(node, offset);
+            self.len -= 1;
+
+            let len = (*node.as_ptr()).len;
+            if len == 0 {
+                self.unlink(node);
+            } else if len < N / 2 {
+                let fits = |other: NonNull<Node<T, N>>| (*other.as_ptr()).len + len <= N;
+                match ((*node.as_ptr()).prev, (*node.as_ptr()).next) {
+                    (_, Some(next)) if fits(next) => {
+                        Node::move_tail(next, 0, node);
+                        self.unlink(next);
+                    }
+                    (Some(prev), _) if fits(prev) => {
+                        Node::move_tail(node, 0, prev);
+                        self.unlink(node);
+                    }
+                    // both neighbors are dense enough
+                    _ => {}
+                }
+            }
+            Some(elem)
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    // Finds the node holding element `index` and the offset inside it,
+    // walking from the closer end. `index` must be in bounds.
+    unsafe fn locate(&self, index: usize) -> (NonNull<Node<T, N>>, usize) {
+        debug_assert!(index < self.len);
+        if index < self.len / 2 {
+            let mut node = self.front.unwrap();
+            let mut offset = index;
+            while offset >= (*node.as_ptr()).len {
+                offset -= (*node.as_ptr()).len;
+                node = (*node.as_ptr()).next.unwrap();
+            }
+            (node, offset)
+        } else {
+            // count from the back: `back_offset` elements follow `index`
+            let mut node = self.back.unwrap();
+            let mut back_offset = self.len - 1 - index;
+            while back_offset >= (*node.as_ptr()).len {
+                back_offset -= (*node.as_ptr()).len;
+                node = (*node.as_ptr()).prev.unwrap();
+            }
+            (node, (*node.as_ptr()).len - 1 - back_offset)
+        }
+    }
+
+    // Allocates an empty node and links it after `prev`, or at the front if
+    // `prev` is None.
+    unsafe fn link_after(&mut self, prev: Link<T, N>) -> NonNull<Node<T, N>> {
+        let node = Node::new();
+        let next = match prev {
+            Some(prev) => (*prev.as_ptr()).next.replace(node),
+            None => self.front.replace(node),
+        };
+        (*node.as_ptr()).prev = prev;
+        (*node.as_ptr()).next = next;
+        match next {
+            Some(next) => (*next.as_ptr()).prev = Some(node),
+            None => self.back = Some(node),
+        }
+        node
+    }
+
+    // Unlinks an empty node and frees it.
+    unsafe fn unlink(&mut self, node: NonNull<Node<T, N>>) {
+        debug_assert!((*node.as_ptr()).len == 0);
+        drop(self.unlink_node(node));
+    }
+
+    // Unlinks a node and hands it back, its elements still count in `len`.
+    unsafe fn unlink_node(&mut self, node: NonNull<Node<T, N>>) -> Box<Node<T, N>> {
+        let boxed = Box::from_raw(node.as_ptr());
+        match boxed.prev {
+            Some(prev) => (*prev.as_ptr()).next = boxed.next,
+            None => self.front = boxed.next,
+        }
+        match boxed.next {
+            Some(next) => (*next.as_ptr()).prev = boxed.prev,
+            None => self.back = boxed.prev,
+        }
+        boxed
+    }
+
+    // Detaches the whole front node, for Drop.
+    fn pop_front_node(&mut self) -> Option<Box<Node<T, N>>> {
+        self.front.map(|node| unsafe {
+            let boxed = self.unlink_node(node);
+            self.len -= boxed.len;
+            boxed
+        })
+    }
+}
+
+impl<T, const N: usize> Drop for UnrolledList<T, N> {
+    fn drop(&mut self) {
+        // Drops a node's elements in place instead of popping them one by one.
+        // If an element panics, the rest of its slice is still dropped by
+        // drop_in_place, the other slice by `Rest` and the guard takes care of
+        // the following nodes.
+        struct DropGuard<'a, T, const N: usize>(&'a mut UnrolledList<T, N>);
+        impl<'a, T, const N: usize> Drop for DropGuard<'a, T, N> {
+            fn drop(&mut self) {
+                while let Some(mut node) = self.0.pop_front_node() {
+                    unsafe { drop_elems(&mut node) };
+                }
+            }
+        }
+
+        unsafe fn drop_elems<T, const N: usize>(node: &mut Node<T, N>) {
+            struct Rest<T>(*mut [T]);
+            impl<T> Drop for Rest<T> {
+                fn drop(&mut self) {
+                    unsafe { ptr::drop_in_place(self.0) }
+                }
+            }
+
+            let (first, second) = Node::as_slices(node);
+            let _rest = Rest(second);
+            ptr::drop_in_place(first);
+        }
+
+        while let Some(mut node) = self.pop_front_node() {
+            let guard = DropGuard(self);
+            unsafe { drop_elems(&mut node) };
+            std::mem::forget(guard);
+        }
+    }
+}
+
+// const iterator
+pub struct Iter<'a, T, const N: usize> {
+    front: Link<T, N>,
+    // next slot to yield in `front`
+    front_index: usize,
+    back: Link<T, N>,
+    // one past the next slot to yield in `back`
+    back_index: usize,
+    len: usize,
+    _boo: PhantomData<&'a T>,
+}
+
+impl<'a, T, const N: usize> Iterator for Iter<'a, T, N> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        self.front.map(|node| unsafe {
+            let elem = &*Node::slot(node, self.front_index);
+            self.front_index += 1;
+            if self.front_index == (*node.as_ptr()).len {
+                self.front = (*node.as_ptr()).next;
+                self.front_index = 0;
+            }
+            elem
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T, const N: usize> DoubleEndedIterator for Iter<'a, T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        self.back.map(|node| unsafe {
+            self.back_index -= 1;
+            let elem = &*Node::slot(node, self.back_index);
+            if self.back_index == 0 {
+                self.back = (*node.as_ptr()).prev;
+                self.back_index = self.back.map_or(0, |prev| (*prev.as_ptr()).len);
+            }
+            elem
+        })
+    }
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for Iter<'a, T, N> {}
+
+impl<T, const N: usize> UnrolledList<T, N> {
+    pub fn iter(&self) -> Iter<'_, T, N> {
+        Iter {
+            front: self.front,
+            front_index: 0,
+            back: self.back,
+            back_index: self.back.map_or(0, |node| unsafe { (*node.as_ptr()).len }),
+            len: self.len,
+            _boo: PhantomData,
+        }
+    }
+}
+
+// mutable iterator
+pub struct IterMut<'a, T, const N: usize> {
+    front: Link<T, N>,
+    front_index: usize,
+    back: Link<T, N>,
+    back_index: usize,
+    len: usize,
+    _boo: PhantomData<&'a mut T>,
+}
+
+impl<'a, T, const N: usize> Iterator for IterMut<'a, T, N> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        self.front.map(|node| unsafe {
+            let elem = &mut *Node::slot(node, self.front_index);
+            self.front_index += 1;
+            if self.front_index == (*node.as_ptr()).len {
+                self.front = (*node.as_ptr()).next;
+                self.front_index = 0;
+            }
+            elem
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T, const N: usize> DoubleEndedIterator for IterMut<'a, T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        self.back.map(|node| unsafe {
+            self.back_index -= 1;
+            let elem = &mut *Node::slot(node, self.back_index);
+            if self.back_index == 0 {
+                self.back = (*node.as_ptr()).prev;
+                self.back_index = self.back.map_or(0, |prev| (*prev.as_ptr()).len);
+            }
+            elem
+        })
+    }
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for IterMut<'a, T, N> {}
+
+impl<T, const N: usize> UnrolledList<T, N> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, N> {
+        IterMut {
+            front: self.front,
+            front_index: 0,
+            back: self.back,
+            back_index: self.back.map_or(0, |node| unsafe { (*node.as_ptr()).len }),
+            len: self.len,
+            _boo: PhantomData,
+        }
+    }
+}
+
+// into iterator
+pub struct IntoIter<T, const N: usize>(UnrolledList<T, N>);
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.pop_back()
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> UnrolledList<T, N> {
+    pub fn into_iter(self) -> IntoIter<T, N> {
+        IntoIter(self)
+    }
+}
+
+//////////////////////
+// other interfaces //
+//////////////////////
+
+impl<T, const N: usize> Default for UnrolledList<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: std::hash::Hash, const N: usize> std::hash::Hash for UnrolledList<T, N> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for item in self.iter() {
+            item.hash(state);
+        }
+    }
+}
+
+impl<T: std::fmt::Debug, const N: usize> std::fmt::Debug for UnrolledList<T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for UnrolledList<T, N> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T, const N: usize> Extend<T> for UnrolledList<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push_back(item);
+        }
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for UnrolledList<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for UnrolledList<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+impl<T: Eq, const N: usize> Eq for UnrolledList<T, N> {}
+
+impl<T: PartialOrd, const N: usize> PartialOrd for UnrolledList<T, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+impl<T: Ord, const N: usize> Ord for UnrolledList<T, N> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+unsafe impl<T: Send, const N: usize> Send for UnrolledList<T, N> {}
+unsafe impl<T: Sync, const N: usize> Sync for UnrolledList<T, N> {}
+
+unsafe impl<'a, T: Send, const N: usize> Send for Iter<'a, T, N> {}
+unsafe impl<'a, T: Sync, const N: usize> Sync for Iter<'a, T, N> {}
+
+unsafe impl<'a, T: Send, const N: usize> Send for IterMut<'a, T, N> {}
+unsafe impl<'a, T: Sync, const N: usize> Sync for IterMut<'a, T, N> {}
+
+// test Sync and Send traits
+#[allow(dead_code)]
+fn assert_properties() {
+    fn is_send<T: Send>() {}
+    fn is_sync<T: Sync>() {}
+
+    is_send::<UnrolledList<i32, 8>>();
+    is_sync::<UnrolledList<i32, 8>>();
+
+    is_send::<IntoIter<i32, 8>>();
+    is_sync::<IntoIter<i32, 8>>();
+
+    is_send::<Iter<i32, 8>>();
+    is_sync::<Iter<i32, 8>>();
+
+    is_send::<IterMut<i32, 8>>();
+    is_sync::<IterMut<i32, 8>>();
+
+    fn list_covariant<'a, T>(x: UnrolledList<&'static T, 8>) -> UnrolledList<&'a T, 8> {
+        x
+    }
+    fn iter_covariant<'i, 'a, T>(x: Iter<'i, &'static T, 8>) -> Iter<'i, &'a T, 8> {
+        x
+    }
+    fn into_iter_covariant<'a, T>(x: IntoIter<&'static T, 8>) -> IntoIter<&'a T, 8> {
+        x
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::UnrolledList;
+    use crate::test_helpers::{panics, DropCounter};
+    use std::collections::VecDeque;
+
+    // fill counts of every node, front to back, checking the links on the way
+    fn fills<T, const N: usize>(list: &UnrolledList<T, N>) -> Vec<usize> {
+        let mut fills = Vec::new();
+        let mut prev = None;
+        let mut cur = list.front;
+        unsafe {
+            while let Some(node) = cur {
+                assert_eq!((*node.as_ptr()).prev, prev);
+                let len = (*node.as_ptr()).len;
+                assert!(len > 0 && len <= N);
+                fills.push(len);
+                prev = cur;
+                cur = (*node.as_ptr()).next;
+            }
+        }
+        assert_eq!(list.back, prev);
+        assert_eq!(fills.iter().sum::<usize>(), list.len());
+        fills
+    }
+
+    #[test]
+    fn basics() {
+        let mut list = UnrolledList::<i32, 4>::new();
+        assert!(list.is_empty());
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.pop_back(), None);
+
+        for i in 0..10 {
+            list.push_back(i);
+        }
+        assert_eq!(list.len(), 10);
+        assert_eq!(fills(&list), vec![4, 4, 2]);
+        assert_eq!(list.front(), Some(&0));
+        assert_eq!(list.back(), Some(&9));
+
+        list.push_front(-1);
+        assert_eq!(fills(&list), vec![1, 4, 4, 2]);
+        list.push_front(-2);
+        assert_eq!(fills(&list), vec![2, 4, 4, 2]);
+
+        assert_eq!(list.pop_front(), Some(-2));
+        assert_eq!(list.pop_front(), Some(-1));
+        assert_eq!(list.pop_back(), Some(9));
+        assert_eq!(list.pop_back(), Some(8));
+        assert_eq!(fills(&list), vec![4, 4]);
+
+        *list.front_mut().unwrap() = 100;
+        *list.back_mut().unwrap() = 700;
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![100, 1, 2, 3, 4, 5, 6, 700]
+        );
+
+        while list.pop_back().is_some() {}
+        assert!(list.is_empty());
+        assert_eq!(fills(&list), vec![]);
+        assert_eq!(list.front(), None);
+
+        // the exhaustion case fixed the pointers right
+        list.push_front(1);
+        list.push_back(2);
+        assert_eq!(fills(&list), vec![2]);
+    }
+
+    #[test]
+    fn iterators() {
+        let mut list: UnrolledList<i32, 3> = (0..10).collect();
+
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 10);
+        assert_eq!(iter.next(), Some(&0));
+        assert_eq!(iter.next_back(), Some(&9));
+        assert_eq!(iter.next_back(), Some(&8));
+        assert_eq!(iter.next_back(), Some(&7));
+        assert_eq!(iter.next_back(), Some(&6));
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.copied().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+        assert!(list.iter().rev().copied().eq((0..10).rev()));
+
+        for elem in list.iter_mut() {
+            *elem *= 10;
+        }
+        let mut iter = list.iter_mut();
+        assert_eq!(iter.next_back(), Some(&mut 90));
+        assert_eq!(iter.next(), Some(&mut 0));
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.len(), 10);
+        assert_eq!(iter.next_back(), Some(90));
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(
+            iter.collect::<Vec<_>>(),
+            vec![10, 20, 30, 40, 50, 60, 70, 80]
+        );
+    }
+
+    #[test]
+    fn get() {
+        let mut list: UnrolledList<i32, 4> = (0..11).collect();
+        list.push_front(-1);
+        for i in 0..12 {
+            assert_eq!(list.get(i), Some(&(i as i32 - 1)));
+        }
+        assert_eq!(list.get(12), None);
+
+        *list.get_mut(6).unwrap() = 42;
+        assert_eq!(list.get(6), Some(&42));
+    }
+
+    #[test]
+    fn insert_splits() {
+        let mut list: UnrolledList<i32, 4> = (0..8).collect();
+        assert_eq!(fills(&list), vec![4, 4]);
+
+        // full node is split into two halves
+        list.insert(1, 100);
+        assert_eq!(fills(&list), vec![3, 2, 4]);
+        list.insert(4, 200);
+        assert_eq!(fills(&list), vec![3, 3, 4]);
+        list.insert(10, 300);
+        assert_eq!(fills(&list), vec![3, 3, 4, 1]);
+        list.insert(0, 400);
+        assert_eq!(fills(&list), vec![4, 3, 4, 1]);
+
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![400, 0, 100, 1, 2, 200, 3, 4, 5, 6, 7, 300]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_out_of_bounds() {
+        let mut list: UnrolledList<i32, 4> = (0..3).collect();
+        list.insert(4, 0);
+    }
+
+    #[test]
+    fn remove_merges() {
+        let mut list: UnrolledList<i32, 4> = (0..12).collect();
+        assert_eq!(fills(&list), vec![4, 4, 4]);
+
+        assert_eq!(list.remove(4), Some(4));
+        assert_eq!(list.remove(4), Some(5));
+        assert_eq!(fills(&list), vec![4, 2, 4]);
+        // below half full, but doesn't fit into a neighbor
+        assert_eq!(list.remove(4), Some(6));
+        assert_eq!(fills(&list), vec![4, 1, 4]);
+        assert_eq!(list.remove(12), None);
+
+        // the front node shrinks and takes in the next one
+        assert_eq!(list.remove(0), Some(0));
+        assert_eq!(list.remove(0), Some(1));
+        assert_eq!(fills(&list), vec![2, 1, 4]);
+        assert_eq!(list.remove(0), Some(2));
+        assert_eq!(fills(&list), vec![2, 4]);
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![3, 7, 8, 9, 10, 11]
+        );
+
+        // the back node shrinks and moves into the previous one
+        let mut list: UnrolledList<i32, 4> = (0..8).collect();
+        list.remove(0);
+        list.remove(0);
+        assert_eq!(fills(&list), vec![2, 4]);
+        assert_eq!(list.remove(5), Some(7));
+        assert_eq!(list.remove(4), Some(6));
+        assert_eq!(list.remove(3), Some(5));
+        assert_eq!(fills(&list), vec![3]);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn ring() {
+        // front pushes fill a node from its end, nothing shifts
+        let mut list = UnrolledList::<i32, 4>::new();
+        for i in 0..4 {
+            list.push_front(i);
+        }
+        let front = list.front.unwrap();
+        // the first one went to slot 0, the others before it
+        assert_eq!(unsafe { (*front.as_ptr()).start }, 1);
+        assert_eq!(fills(&list), vec![4]);
+        // popping one end and pushing the other wraps around
+        assert_eq!(list.pop_back(), Some(0));
+        list.push_front(4);
+        assert_eq!(list.pop_back(), Some(1));
+        list.push_front(5);
+        assert_eq!(unsafe { (*front.as_ptr()).start }, 3);
+        assert_eq!(fills(&list), vec![4]);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![5, 4, 3, 2]);
+        assert_eq!(
+            list.iter().rev().copied().collect::<Vec<_>>(),
+            vec![2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn against_vecdeque() {
+        let mut list = UnrolledList::<i32, 5>::new();
+        let mut model = VecDeque::new();
+        let mut seed = 0x9e37_79b9_7f4a_7c15u64;
+        for i in 0..5000 {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            let at = (seed >> 32) as usize % (model.len() + 1);
+            match seed % 6 {
+                0 => {
+                    list.push_front(i);
+                    model.push_front(i);
+                }
+                1 => {
+                    list.push_back(i);
+                    model.push_back(i);
+                }
+                2 => assert_eq!(list.pop_front(), model.pop_front()),
+                3 => assert_eq!(list.pop_back(), model.pop_back()),
+                4 => {
+                    list.insert(at, i);
+                    model.insert(at, i);
+                }
+                _ => assert_eq!(list.remove(at), model.remove(at)),
+            }
+            fills(&list);
+            assert!(list.iter().eq(model.iter()));
+            assert!(list.iter().rev().eq(model.iter().rev()));
+        }
+    }
+
+    #[test]
+    fn traits() {
+        let list: UnrolledList<i32, 4> = (0..10).collect();
+        assert_eq!(format!("{:?}", list), "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]");
+
+        let mut other = list.clone();
+        assert_eq!(list, other);
+        other.pop_back();
+        assert!(other < list);
+        other.push_back(10);
+        assert!(other > list);
+        assert!(list != other);
+    }
+
+    #[test]
+    fn drop_order() {
+        let counter = DropCounter::new();
+        let mut list = UnrolledList::<_, 3>::new();
+        for id in 0..7 {
+            list.push_back(counter.track(id));
+        }
+        list.remove(3);
+        assert_eq!(counter.dropped(), vec![3]);
+
+        drop(list);
+        assert_eq!(counter.dropped(), vec![3, 0, 1, 2, 4, 5, 6]);
+    }
+
+    #[test]
+    fn drop_panic() {
+        let counter = DropCounter::new();
+        let mut list = UnrolledList::<_, 3>::new();
+        for id in 0..7 {
+            if id == 1 {
+                list.push_back(counter.panic_on_drop(id));
+            } else {
+                list.push_back(counter.track(id));
+            }
+        }
+
+        // the rest of the node and the following nodes are still dropped
+        assert!(panics(move || drop(list)));
+        assert_eq!(counter.dropped(), vec![0, 1, 2, 3, 4, 5, 6]);
+
+        // a node that wraps around is two slices, both are dropped
+        let mut list = UnrolledList::<_, 4>::new();
+        list.push_back(counter.track(10));
+        list.push_back(counter.track(11));
+        list.push_front(counter.panic_on_drop(12));
+        list.push_front(counter.track(13));
+        assert!(panics(move || drop(list)));
+        assert_eq!(counter.dropped()[7..], [13, 12, 10, 11]);
+    }
+
+    #[test]
+    fn zero_sized() {
+        let mut list = UnrolledList::<(), 4>::new();
+        for _ in 0..10 {
+            list.push_back(());
+        }
+        list.insert(5, ());
+        assert_eq!(list.remove(2), Some(()));
+        assert_eq!(list.iter().count(), 10);
+    }
+}
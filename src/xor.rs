@@ -0,0 +1,812 @@
+// "An XOR-Linked Deque", the deque of "sixth.rs" with one link per node.
+// To learn about:
+//   1) XOR linking
+//   2) Pointer provenance
+
+// Takeaways:
+//   1. A node stores `addr(prev) ^ addr(next)` instead of two pointers, an
+//      empty link counts as address 0. Knowing the address of one neighbor,
+//      we can recover the other one: `both ^ addr(prev) == addr(next)`.
+//   2. So we can only walk the list from an end, always carrying the pair
+//      (node, node we came from). There is no way to start from a node in
+//      the middle, which is why this list has no cursor.
+//   3. Turning a pointer into an integer and back loses its "provenance",
+//      the compiler no longer knows which allocation it may access. We use
+//      `expose_provenance` / `with_exposed_provenance_mut`, which tell the
+//      compiler that the address escapes and may be turned back into a pointer.
+//   4. For small T it's a real memory win, a `Node<u32>` is 16 bytes instead
+//      of 24 with two pointers.
+
+use std::marker::PhantomData;
+use std::ptr::{self, NonNull};
+
+pub struct XorLinkedList<T> {
+    front: Link<T>,
+    back: Link<T>,
+    len: usize,
+    // We semantically store values of T by-value.
+    _boo: PhantomData<T>,
+}
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+struct Node<T> {
+    // addr(prev) ^ addr(next)
+    both: usize,
+    elem: T,
+}
+
+fn addr<T>(link: Link<T>) -> usize {
+    link.map_or(0, |node| node.as_ptr().expose_provenance())
+}
+
+fn from_addr<T>(addr: usize) -> Link<T> {
+    NonNull::new(ptr::with_exposed_provenance_mut(addr))
+}
+
+// given a node and one of its neighbors, returns the other neighbor
+unsafe fn other<T>(node: NonNull<Node<T>>, neighbor: Link<T>) -> Link<T> {
+    from_addr((*node.as_ptr()).both ^ addr(neighbor))
+}
+
+impl<T> XorLinkedList<T> {
+    pub fn new() -> Self {
+        Self {
+            front: None,
+            back: None,
+            len: 0,
+            _boo: PhantomData,
+        }
+    }
+
+    // Links a new node outside of `end`, the front or the back, the list is
+    // symmetric. `other_end` is only touched if the list was empty.
+    unsafe fn push_end(end: &mut Link<T>, other_end: &mut Link<T>, elem: T) {
+        // the new node's neighbors are None and the old end
+        let new_end = NonNull::new_unchecked(Box::into_raw(Box::new(Node {
+            both: addr(*end),
+            elem,
+        })));
+
+        match *end {
+            // old end: (None ^ next) becomes (new_end ^ next)
+            Some(old_end) => (*old_end.as_ptr()).both ^= addr(Some(new_end)),
+            None => *other_end = Some(new_end),
+        }
+        *end = Some(new_end);
+    }
+
+    unsafe fn pop_end(end: &mut Link<T>, other_end: &mut Link<T>) -> Option<T> {
+        end.map(|node| {
+            // decode before Box claims unique ownership of the node
+            let new_end = other(node, None);
+            let boxed_node = Box::from_raw(node.as_ptr());
+
+            match new_end {
+                // new end: (node ^ next) becomes (None ^ next)
+                Some(new_end) => (*new_end.as_ptr()).both ^= addr(Some(node)),
+                None => *other_end = None,
+            }
+            *end = new_end;
+            boxed_node.elem
+        })
+    }
+
+    pub fn push_front(&mut self, elem: T) {
+        unsafe { Self::push_end(&mut self.front, &mut self.back, elem) }
+        self.len += 1;
+    }
+
+    pub fn push_back(&mut self, elem: T) {
+        unsafe { Self::push_end(&mut self.back, &mut self.front, elem) }
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let elem = unsafe { Self::pop_end(&mut self.front, &mut self.back) };
+        if elem.is_some() {
+            self.len -= 1;
+        }
+        elem
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let elem = unsafe { Self::pop_end(&mut self.back, &mut self.front) };
+        if elem.is_some() {
+            self.len -= 1;
+        }
+        elem
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.front.map(|node| unsafe { &(*node.as_ptr()).elem })
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.front.map(|node| unsafe { &mut (*node.as_ptr()).elem })
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.back.map(|node| unsafe { &(*node.as_ptr()).elem })
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.back.map(|node| unsafe { &mut (*node.as_ptr()).elem })
+    }
+}
+
+impl<T> Drop for XorLinkedList<T> {
+    fn drop(&mut self) {
+        // keep popping the rest if dropping an element panics, see "sixth.rs"
+        struct DropGuard<'a, T>(&'a mut XorLinkedList<T>);
+        impl<'a, T> Drop for DropGuard<'a, T> {
+            fn drop(&mut self) {
+                while self.0.pop_front().is_some() {}
+            }
+        }
+
+        while let Some(elem) = self.pop_front() {
+            let guard = DropGuard(self);
+            drop(elem);
+            std::mem::forget(guard);
+        }
+    }
+}
+
+// Both ends of an iterator walk inwards, each remembering the node it came
+// from so that it can decode the next one.
+struct Walker<T> {
+    front: Link<T>,
+    front_prev: Link<T>,
+    back: Link<T>,
+    back_next: Link<T>,
+    len: usize,
+}
+
+impl<T> Walker<T> {
+    fn new(list: &XorLinkedList<T>) -> Self {
+        Walker {
+            front: list.front,
+            front_prev: None,
+            back: list.back,
+            back_next: None,
+            len: list.len,
+        }
+    }
+
+    fn next(&mut self) -> Link<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        self.front.inspect(|&node| unsafe {
+            self.front = other(node, self.front_prev);
+            self.front_prev = Some(node);
+        })
+    }
+
+    fn next_back(&mut self) -> Link<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        self.back.inspect(|&node| unsafe {
+            self.back = other(node, self.back_next);
+            self.back_next = Some(node);
+        })
+    }
+}
+
+// const iterator
+pub struct Iter<'a, T> {
+    walker: Walker<T>,
+    _boo: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.walker
+            .next()
+            .map(|node| unsafe { &(*node.as_ptr()).elem })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.walker.len, Some(self.walker.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.walker
+            .next_back()
+            .map(|node| unsafe { &(*node.as_ptr()).elem })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<T> XorLinkedList<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            walker: Walker::new(self),
+            _boo: PhantomData,
+        }
+    }
+}
+
+// mutable iterator
+pub struct IterMut<'a, T> {
+    walker: Walker<T>,
+    _boo: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.walker
+            .next()
+            .map(|node| unsafe { &mut (*node.as_ptr()).elem })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.walker.len, Some(self.walker.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.walker
+            .next_back()
+            .map(|node| unsafe { &mut (*node.as_ptr()).elem })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<T> XorLinkedList<T> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            walker: Walker::new(self),
+            _boo: PhantomData,
+        }
+    }
+}
+
+// into iterator
+pub struct IntoIter<T>(XorLinkedList<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> XorLinkedList<T> {
+    pub fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+//////////////////////
+// other interfaces //
+//////////////////////
+
+impl<T> Default for XorLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: std::hash::Hash> std::hash::Hash for XorLinkedList<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for item in self.iter() {
+            item.hash(state);
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for XorLinkedList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Clone> Clone for XorLinkedList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> Extend<T> for XorLinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push_back(item);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for XorLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T: PartialEq> PartialEq for XorLinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+impl<T: Eq> Eq for XorLinkedList<T> {}
+
+impl<T: PartialOrd> PartialOrd for XorLinkedList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+impl<T: Ord> Ord for XorLinkedList<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+unsafe impl<T: Send> Send for XorLinkedList<T> {}
+unsafe impl<T: Sync> Sync for XorLinkedList<T> {}
+
+unsafe impl<'a, T: Send> Send for Iter<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
+
+unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
+
+// test Sync and Send traits
+#[allow(dead_code)]
+fn assert_properties() {
+    fn is_send<T: Send>() {}
+    fn is_sync<T: Sync>() {}
+
+    is_send::<XorLinkedList<i32>>();
+    is_sync::<XorLinkedList<i32>>();
+
+    is_send::<IntoIter<i32>>();
+    is_sync::<IntoIter<i32>>();
+
+    is_send::<Iter<i32>>();
+    is_sync::<Iter<i32>>();
+
+    is_send::<IterMut<i32>>();
+    is_sync::<IterMut<i32>>();
+
+    fn linked_list_covariant<'a, T>(x: XorLinkedList<&'static T>) -> XorLinkedList<&'a T> {
+        x
+    }
+    fn iter_covariant<'i, 'a, T>(x: Iter<'i, &'static T>) -> Iter<'i, &'a T> {
+        x
+    }
+    fn into_iter_covariant<'a, T>(x: IntoIter<&'static T>) -> IntoIter<&'a T> {
+        x
+    }
+}
+
+// the test suite of "sixth.rs", itself ported from std's LinkedList
+#[cfg(test)]
+#[allow(clippy::manual_next_back, clippy::neg_cmp_op_on_partial_ord)]
+#[allow(clippy::zero_divided_by_zero, clippy::useless_vec)]
+mod test {
+    use super::XorLinkedList;
+    use crate::test_helpers::{panics, DropCounter};
+
+    fn generate_test() -> XorLinkedList<i32> {
+        list_from(&[0, 1, 2, 3, 4, 5, 6])
+    }
+
+    fn list_from<T: Clone>(v: &[T]) -> XorLinkedList<T> {
+        v.iter().map(|x| (*x).clone()).collect()
+    }
+
+    #[test]
+    fn test_basic_front() {
+        let mut list = XorLinkedList::new();
+
+        // Try to break an empty list
+        assert_eq!(list.len(), 0);
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.len(), 0);
+
+        // Try to break a one item list
+        list.push_front(10);
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.pop_front(), Some(10));
+        assert_eq!(list.len(), 0);
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.len(), 0);
+
+        // Mess around
+        list.push_front(10);
+        assert_eq!(list.len(), 1);
+        list.push_front(20);
+        assert_eq!(list.len(), 2);
+        list.push_front(30);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.pop_front(), Some(30));
+        assert_eq!(list.len(), 2);
+        list.push_front(40);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.pop_front(), Some(40));
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.pop_front(), Some(20));
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.pop_front(), Some(10));
+        assert_eq!(list.len(), 0);
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.len(), 0);
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.len(), 0);
+    }
+
+    #[test]
+    fn test_basic() {
+        let mut m = XorLinkedList::new();
+        assert_eq!(m.pop_front(), None);
+        assert_eq!(m.pop_back(), None);
+        assert_eq!(m.pop_front(), None);
+        m.push_front(1);
+        assert_eq!(m.pop_front(), Some(1));
+        m.push_back(2);
+        m.push_back(3);
+        assert_eq!(m.len(), 2);
+        assert_eq!(m.pop_front(), Some(2));
+        assert_eq!(m.pop_front(), Some(3));
+        assert_eq!(m.len(), 0);
+        assert_eq!(m.pop_front(), None);
+        m.push_back(1);
+        m.push_back(3);
+        m.push_back(5);
+        m.push_back(7);
+        assert_eq!(m.pop_front(), Some(1));
+
+        let mut n = XorLinkedList::new();
+        n.push_front(2);
+        n.push_front(3);
+        {
+            assert_eq!(n.front().unwrap(), &3);
+            let x = n.front_mut().unwrap();
+            assert_eq!(*x, 3);
+            *x = 0;
+        }
+        {
+            assert_eq!(n.back().unwrap(), &2);
+            let y = n.back_mut().unwrap();
+            assert_eq!(*y, 2);
+            *y = 1;
+        }
+        assert_eq!(n.pop_front(), Some(0));
+        assert_eq!(n.pop_front(), Some(1));
+    }
+
+    #[test]
+    fn test_iterator() {
+        let m = generate_test();
+        for (i, elt) in m.iter().enumerate() {
+            assert_eq!(i as i32, *elt);
+        }
+        let mut n = XorLinkedList::new();
+        assert_eq!(n.iter().next(), None);
+        n.push_front(4);
+        let mut it = n.iter();
+        assert_eq!(it.size_hint(), (1, Some(1)));
+        assert_eq!(it.next().unwrap(), &4);
+        assert_eq!(it.size_hint(), (0, Some(0)));
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn test_iterator_double_end() {
+        let mut n = XorLinkedList::new();
+        assert_eq!(n.iter().next(), None);
+        n.push_front(4);
+        n.push_front(5);
+        n.push_front(6);
+        let mut it = n.iter();
+        assert_eq!(it.size_hint(), (3, Some(3)));
+        assert_eq!(it.next().unwrap(), &6);
+        assert_eq!(it.size_hint(), (2, Some(2)));
+        assert_eq!(it.next_back().unwrap(), &4);
+        assert_eq!(it.size_hint(), (1, Some(1)));
+        assert_eq!(it.next_back().unwrap(), &5);
+        assert_eq!(it.next_back(), None);
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn test_rev_iter() {
+        let m = generate_test();
+        for (i, elt) in m.iter().rev().enumerate() {
+            assert_eq!(6 - i as i32, *elt);
+        }
+        let mut n = XorLinkedList::new();
+        assert_eq!(n.iter().rev().next(), None);
+        n.push_front(4);
+        let mut it = n.iter().rev();
+        assert_eq!(it.size_hint(), (1, Some(1)));
+        assert_eq!(it.next().unwrap(), &4);
+        assert_eq!(it.size_hint(), (0, Some(0)));
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn test_mut_iter() {
+        let mut m = generate_test();
+        let mut len = m.len();
+        for (i, elt) in m.iter_mut().enumerate() {
+            assert_eq!(i as i32, *elt);
+            len -= 1;
+        }
+        assert_eq!(len, 0);
+        let mut n = XorLinkedList::new();
+        assert!(n.iter_mut().next().is_none());
+        n.push_front(4);
+        n.push_back(5);
+        let mut it = n.iter_mut();
+        assert_eq!(it.size_hint(), (2, Some(2)));
+        assert!(it.next().is_some());
+        assert!(it.next().is_some());
+        assert_eq!(it.size_hint(), (0, Some(0)));
+        assert!(it.next().is_none());
+    }
+
+    #[test]
+    fn test_iterator_mut_double_end() {
+        let mut n = XorLinkedList::new();
+        assert!(n.iter_mut().next_back().is_none());
+        n.push_front(4);
+        n.push_front(5);
+        n.push_front(6);
+        let mut it = n.iter_mut();
+        assert_eq!(it.size_hint(), (3, Some(3)));
+        assert_eq!(*it.next().unwrap(), 6);
+        assert_eq!(it.size_hint(), (2, Some(2)));
+        assert_eq!(*it.next_back().unwrap(), 4);
+        assert_eq!(it.size_hint(), (1, Some(1)));
+        assert_eq!(*it.next_back().unwrap(), 5);
+        assert!(it.next_back().is_none());
+        assert!(it.next().is_none());
+    }
+
+    #[test]
+    fn test_eq() {
+        let mut n: XorLinkedList<u8> = list_from(&[]);
+        let mut m = list_from(&[]);
+        assert!(n == m);
+        n.push_front(1);
+        assert!(n != m);
+        m.push_back(1);
+        assert!(n == m);
+
+        let n = list_from(&[2, 3, 4]);
+        let m = list_from(&[1, 2, 3]);
+        assert!(n != m);
+    }
+
+    #[test]
+    fn test_ord() {
+        let n = list_from(&[]);
+        let m = list_from(&[1, 2, 3]);
+        assert!(n < m);
+        assert!(m > n);
+        assert!(n <= n);
+        assert!(n >= n);
+    }
+
+    #[test]
+    fn test_ord_nan() {
+        let nan = 0.0f64 / 0.0;
+        let n = list_from(&[nan]);
+        let m = list_from(&[nan]);
+        assert!(!(n < m));
+        assert!(!(n > m));
+        assert!(!(n <= m));
+        assert!(!(n >= m));
+
+        let n = list_from(&[nan]);
+        let one = list_from(&[1.0f64]);
+        assert!(!(n < one));
+        assert!(!(n > one));
+        assert!(!(n <= one));
+        assert!(!(n >= one));
+
+        let u = list_from(&[1.0f64, 2.0, nan]);
+        let v = list_from(&[1.0f64, 2.0, 3.0]);
+        assert!(!(u < v));
+        assert!(!(u > v));
+        assert!(!(u <= v));
+        assert!(!(u >= v));
+
+        let s = list_from(&[1.0f64, 2.0, 4.0, 2.0]);
+        let t = list_from(&[1.0f64, 2.0, 3.0, 2.0]);
+        assert!(!(s < t));
+        assert!(s > one);
+        assert!(!(s <= one));
+        assert!(s >= one);
+    }
+
+    #[test]
+    fn test_debug() {
+        let list: XorLinkedList<i32> = (0..10).collect();
+        assert_eq!(format!("{:?}", list), "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]");
+
+        let list: XorLinkedList<&str> = vec!["just", "one", "test", "more"]
+            .iter()
+            .copied()
+            .collect();
+        assert_eq!(format!("{:?}", list), r#"["just", "one", "test", "more"]"#);
+    }
+
+    #[test]
+    fn test_hashmap() {
+        // Check that HashMap works with this as a key
+
+        let list1: XorLinkedList<i32> = (0..10).collect();
+        let list2: XorLinkedList<i32> = (1..11).collect();
+        let mut map = std::collections::HashMap::new();
+
+        assert_eq!(map.insert(list1.clone(), "list1"), None);
+        assert_eq!(map.insert(list2.clone(), "list2"), None);
+
+        assert_eq!(map.len(), 2);
+
+        assert_eq!(map.get(&list1), Some(&"list1"));
+        assert_eq!(map.get(&list2), Some(&"list2"));
+
+        assert_eq!(map.remove(&list1), Some("list1"));
+        assert_eq!(map.remove(&list2), Some("list2"));
+
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_drop_order() {
+        let counter = DropCounter::new();
+        let mut list = XorLinkedList::new();
+        list.push_back(counter.track(2));
+        list.push_front(counter.track(1));
+        list.push_back(counter.track(3));
+        list.push_front(counter.track(0));
+        assert_eq!(counter.count(), 0);
+
+        drop(list);
+        assert_eq!(counter.dropped(), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_drop_panic() {
+        let counter = DropCounter::new();
+        let mut list = XorLinkedList::new();
+        list.push_back(counter.track(0));
+        list.push_back(counter.panic_on_drop(1));
+        list.push_back(counter.track(2));
+        list.push_back(counter.track(3));
+
+        // the nodes after the panicking one are still dropped, exactly once
+        assert!(panics(move || drop(list)));
+        assert_eq!(counter.dropped(), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_clone_panic() {
+        let counter = DropCounter::new();
+        let list: XorLinkedList<_> = vec![
+            counter.track(0),
+            counter.track(1),
+            counter.panic_on_clone(2),
+            counter.track(3),
+        ]
+        .into_iter()
+        .collect();
+
+        // the half-built clone is dropped, the original is untouched
+        assert!(panics(|| drop(list.clone())));
+        assert_eq!(counter.dropped(), vec![0, 1]);
+        assert_eq!(list.len(), 4);
+
+        drop(list);
+        assert_eq!(counter.dropped(), vec![0, 1, 0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_extend_panic() {
+        let counter = DropCounter::new();
+        let mut list = XorLinkedList::new();
+        list.push_back(counter.track(0));
+
+        let iter = (1..5).map(|id| {
+            if id == 3 {
+                panic!("iterator panicked");
+            }
+            counter.track(id)
+        });
+
+        // elements yielded before the panic stay in the list
+        assert!(panics(|| list.extend(iter)));
+        assert_eq!(counter.count(), 0);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.back().map(|x| x.id), Some(2));
+
+        drop(list);
+        assert_eq!(counter.dropped(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_node_size() {
+        // one link per node, `both` plus a u32 padded to a word
+        assert_eq!(
+            std::mem::size_of::<super::Node<u32>>(),
+            2 * std::mem::size_of::<usize>()
+        );
+    }
+
+    #[test]
+    fn test_both_ends() {
+        let mut list = XorLinkedList::new();
+        for i in 0..10 {
+            if i % 2 == 0 {
+                list.push_front(i);
+            } else {
+                list.push_back(i);
+            }
+        }
+        assert_eq!(list.len(), 10);
+        assert!(list.iter().copied().eq([8, 6, 4, 2, 0, 1, 3, 5, 7, 9]));
+        assert!(list
+            .iter()
+            .rev()
+            .copied()
+            .eq([9, 7, 5, 3, 1, 0, 2, 4, 6, 8]));
+
+        // meet in the middle
+        let mut iter = list.iter();
+        for _ in 0..5 {
+            assert!(iter.next().is_some());
+            assert!(iter.next_back().is_some());
+        }
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        assert_eq!(list.pop_back(), Some(9));
+        assert_eq!(list.pop_front(), Some(8));
+        assert_eq!(list.pop_back(), Some(7));
+        assert!(list.iter().copied().eq([6, 4, 2, 0, 1, 3, 5]));
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.len(), 7);
+        assert_eq!(iter.next_back(), Some(5));
+        assert_eq!(iter.next(), Some(6));
+        assert!(iter.eq([4, 2, 0, 1, 3]));
+    }
+}